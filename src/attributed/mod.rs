@@ -0,0 +1,314 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use bitset::BitVec;
+use graph;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, Neighbors};
+use {NodeAttrs, EdgeAttrs};
+
+/// Attributes of a graph, its nodes or one of its edges, by name.
+pub type Attrs = HashMap<String, String>;
+
+/// Graph carrying GraphViz attributes: graph-level ones, defaults for every node and edge, and
+/// per-node and per-edge overrides. Overrides follow their vertices and edges through every
+/// operation that moves or drops them, so they never drift from the topology. An undirected
+/// edge {u, v} is keyed by (min(u, v), max(u, v)).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributedGraph<G: BitGraph> {
+    /// Topology of the graph.
+    graph: G,
+    /// Attributes of the graph itself.
+    graph_attrs: Attrs,
+    /// Attributes of every node unless overridden.
+    node_defaults: Attrs,
+    /// Attributes of every edge unless overridden.
+    edge_defaults: Attrs,
+    /// Per-node overrides.
+    nodes: NodeAttrs,
+    /// Per-edge overrides, only for existing edges.
+    edges: EdgeAttrs
+}
+
+pub fn new<G: BitGraph>(graph: G) -> AttributedGraph<G> {
+    //! Wrap graph with no attributes.
+    AttributedGraph {
+        graph,
+        graph_attrs: Attrs::new(),
+        node_defaults: Attrs::new(),
+        edge_defaults: Attrs::new(),
+        nodes: NodeAttrs::new(),
+        edges: EdgeAttrs::new()
+    }
+}
+
+fn key(directed: bool, u: usize, v: usize) -> (usize, usize) {
+    if directed || u <= v { (u, v) } else { (v, u) }
+}
+
+impl<G: BitGraph> AttributedGraph<G> {
+    fn with_graph<H: BitGraph>(&self, graph: H, nodes: NodeAttrs, edges: EdgeAttrs)
+        -> AttributedGraph<H>
+    {
+        AttributedGraph {
+            graph,
+            graph_attrs: self.graph_attrs.clone(),
+            node_defaults: self.node_defaults.clone(),
+            edge_defaults: self.edge_defaults.clone(),
+            nodes,
+            edges
+        }
+    }
+
+    fn remap<F>(&self, graph: &G, f: F) -> (NodeAttrs, EdgeAttrs)
+        where F: Fn(usize) -> Option<usize>
+    {
+        // move every override to its new id, dropping vertices f drops and edges graph lacks
+        let nodes = self.nodes.iter()
+            .filter_map(|(&v, a)| f(v).map(|w| (w, a.clone()))).collect();
+        let edges = self.edges.iter().filter_map(|(&(u, v), a)| match (f(u), f(v)) {
+            (Some(x), Some(y)) if graph.has_edge(x, y) =>
+                Some((key(graph.is_directed(), x, y), a.clone())),
+            _ => None
+        }).collect();
+        (nodes, edges)
+    }
+
+    fn retain_edges(&mut self) {
+        // drop overrides of edges the topology no longer has
+        let graph = &self.graph;
+        self.edges.retain(|&(u, v), _| graph.has_edge(u, v));
+    }
+
+    pub fn graph(&self) -> &G {
+        //! Return the topology of self.
+        &self.graph
+    }
+
+    pub fn into_inner(self) -> G {
+        //! Drop the attributes and return the topology of self.
+        self.graph
+    }
+
+    pub fn set_graph_attr<K: Into<String>, V: Into<String>>(&mut self, k: K, v: V) {
+        //! Set attribute k of the graph itself.
+        self.graph_attrs.insert(k.into(), v.into());
+    }
+
+    pub fn set_node_default<K: Into<String>, V: Into<String>>(&mut self, k: K, v: V) {
+        //! Set attribute k of every node without its own value for k.
+        self.node_defaults.insert(k.into(), v.into());
+    }
+
+    pub fn set_edge_default<K: Into<String>, V: Into<String>>(&mut self, k: K, v: V) {
+        //! Set attribute k of every edge without its own value for k.
+        self.edge_defaults.insert(k.into(), v.into());
+    }
+
+    pub fn set_node_attr<K, V>(&mut self, id: usize, k: K, v: V) -> Result<(), GraphError>
+        where K: Into<String>, V: Into<String>
+    {
+        //! Set attribute k of node id, or fail if id is out of bounds.
+        self.graph.check_vertex(id)?;
+        self.nodes.entry(id).or_default().insert(k.into(), v.into());
+        Ok(())
+    }
+
+    pub fn set_edge_attr<K, V>(&mut self, fr: usize, to: usize, k: K, v: V)
+        -> Result<(), GraphError>
+        where K: Into<String>, V: Into<String>
+    {
+        //! Set attribute k of the edge from fr to to, or fail if there is no such edge.
+        self.graph.check_vertex(fr)?;
+        self.graph.check_vertex(to)?;
+        if !self.graph.has_edge(fr, to) {
+            return Err(GraphError::MissingEdge(fr, to));
+        }
+        let e = key(self.graph.is_directed(), fr, to);
+        self.edges.entry(e).or_default().insert(k.into(), v.into());
+        Ok(())
+    }
+
+    pub fn graph_attr(&self, k: &str) -> Option<&str> {
+        //! Return attribute k of the graph itself.
+        self.graph_attrs.get(k).map(String::as_str)
+    }
+
+    pub fn node_attr(&self, id: usize, k: &str) -> Option<&str> {
+        //! Return attribute k of node id, falling back to the node default.
+        self.nodes.get(&id).and_then(|a| a.get(k)).or_else(|| self.node_defaults.get(k))
+            .map(String::as_str)
+    }
+
+    pub fn edge_attr(&self, fr: usize, to: usize, k: &str) -> Option<&str> {
+        //! Return attribute k of the edge from fr to to, falling back to the edge default. None
+        //! if there is no such edge.
+        if !self.graph.has_edge(fr, to) {
+            return None;
+        }
+        let e = key(self.graph.is_directed(), fr, to);
+        self.edges.get(&e).and_then(|a| a.get(k)).or_else(|| self.edge_defaults.get(k))
+            .map(String::as_str)
+    }
+
+    pub fn node_attrs(&self) -> &NodeAttrs {
+        //! Return the per-node overrides.
+        &self.nodes
+    }
+
+    pub fn edge_attrs(&self) -> &EdgeAttrs {
+        //! Return the per-edge overrides.
+        &self.edges
+    }
+}
+
+fn attr_list(attrs: &Attrs) -> String {
+    let mut pairs: Vec<String> = attrs.iter().map(|(k, v)| graph::dot_attr(k, v)).collect();
+    pairs.sort();
+    pairs.join(",")
+}
+
+impl<G: BitGraph> GraphRef for AttributedGraph<G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.graph.out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.graph.in_degree(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        self.graph.neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(from, to)
+    }
+}
+
+impl<G: BitGraph> GraphMut for AttributedGraph<G> {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        self.graph.add_edge(fr, to);
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.graph.try_add_edge(fr, to)
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.graph.remove_edge(fr, to);
+        let e = key(self.graph.is_directed(), fr, to);
+        self.edges.remove(&e);
+    }
+    fn add_vertex(&mut self) -> usize {
+        self.graph.add_vertex()
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        // a tombstone keeps its node attributes, since the vertex still exists
+        let moved = self.graph.remove_vertex(v, mode);
+        if mode == Removal::Tombstone {
+            self.retain_edges();
+            return moved;
+        }
+        let last = self.graph.len();
+        let relabel = |x: usize| if x == v { None } else if x == last { Some(v) } else { Some(x) };
+        let (nodes, edges) = self.remap(&self.graph, relabel);
+        self.nodes = nodes;
+        self.edges = edges;
+        moved
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        self.graph.induce(vertices);
+        self.retain_edges();
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        // edges of v move to u; where u already had the edge, its attributes win
+        let (u, v) = e;
+        self.graph.contract(e);
+        let directed = self.graph.is_directed();
+        let moved: Vec<_> = self.edges.keys().filter(|&&(a, b)| a == v || b == v).cloned()
+            .collect();
+        for (a, b) in moved {
+            let attrs = self.edges.remove(&(a, b)).unwrap();
+            let merge = |x: usize| if x == v { u } else { x };
+            let (a, b) = (merge(a), merge(b));
+            if self.graph.has_edge(a, b) {
+                self.edges.entry(key(directed, a, b)).or_insert(attrs);
+            }
+        }
+        self.retain_edges();
+    }
+}
+
+impl<G: BitGraph> BitGraph for AttributedGraph<G> {
+    fn verify(&self) -> bool {
+        //! Make sure the topology is valid and every override belongs to a vertex or edge.
+        let directed = self.graph.is_directed();
+        self.graph.verify() && self.nodes.keys().all(|&v| v < self.graph.len()) &&
+            self.edges.keys().all(|&(u, v)| {
+                v < self.graph.len() && (directed || u <= v) && self.graph.has_edge(u, v)
+            })
+    }
+    fn complement(&self) -> Self {
+        // no edge survives complementing, so only node overrides carry over
+        self.with_graph(self.graph.complement(), self.nodes.clone(), EdgeAttrs::new())
+    }
+    fn compressed(&self) -> (Self, Vec<usize>) {
+        let (graph, map) = self.graph.compressed();
+        let mut inverse = vec![None; self.len()];
+        for (i, &v) in map.iter().enumerate() {
+            inverse[v] = Some(i);
+        }
+        let (nodes, edges) = self.remap(&graph, |v| inverse[v]);
+        (self.with_graph(graph, nodes, edges), map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let graph = self.graph.reordered(order);
+        let mut inverse = vec![None; self.len()];
+        for (i, &v) in order.iter().enumerate() {
+            inverse[v] = Some(i);
+        }
+        let (nodes, edges) = self.remap(&graph, |v| inverse[v]);
+        self.with_graph(graph, nodes, edges)
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        //! Write the stored attributes, with entries of node_attrs and edge_attrs taking
+        //! precedence over the stored overrides.
+        let mut preamble = Vec::new();
+        if !self.graph_attrs.is_empty() {
+            preamble.push(format!("graph [{}]", attr_list(&self.graph_attrs)));
+        }
+        let mut node_defaults: Attrs = [("fontname", "sans-serif"), ("fontsize", "12")].iter()
+            .map(|&(k, v)| (k.to_string(), v.to_string())).collect();
+        node_defaults.extend(self.node_defaults.clone());
+        preamble.push(format!("node [{}]", attr_list(&node_defaults)));
+        if !self.edge_defaults.is_empty() {
+            preamble.push(format!("edge [{}]", attr_list(&self.edge_defaults)));
+        }
+        let mut nodes = self.nodes.clone();
+        for (&v, a) in node_attrs.into_iter().flatten() {
+            nodes.entry(v).or_default().extend(a.clone());
+        }
+        let mut edges = self.edges.clone();
+        for (&e, a) in edge_attrs.into_iter().flatten() {
+            edges.entry(e).or_default().extend(a.clone());
+        }
+        graph::write_dot(self, self.is_directed(), None, preamble, Some(&nodes), Some(&edges))
+    }
+}
@@ -0,0 +1,220 @@
+use std::borrow::Cow;
+use bitset::BitVec;
+use bitset::kernels;
+use graph::{self, Graph};
+use {GraphRef, GraphMut, GraphError, Neighbors};
+
+/// Undirected bipartite graph with parts of size m and n, stored as its m x n biadjacency
+/// matrix. The left part holds vertices 0..m and the right part holds m..m+n, so the graph
+/// answers the same queries as a graph::Graph on m+n vertices in mn bits instead of (m+n)^2.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BipartiteGraph {
+    /// Size of the right part.
+    n: usize,
+    /// rows[i][j] is set if left vertex i is joined to right vertex m+j.
+    rows: Vec<BitVec>
+}
+
+pub fn new(m: usize, n: usize) -> BipartiteGraph {
+    //! Construct new BipartiteGraph with parts of size m and n and no edges.
+    BipartiteGraph {
+        n,
+        rows: vec![BitVec::from_elem(n, false); m]
+    }
+}
+
+pub fn complete(m: usize, n: usize) -> BipartiteGraph {
+    //! Construct K_{m,n}.
+    BipartiteGraph {
+        n,
+        rows: vec![BitVec::from_elem(n, true); m]
+    }
+}
+
+pub fn from_graph(g: &Graph, left: &BitVec)
+    -> Result<(BipartiteGraph, Vec<usize>), GraphError> {
+    //! Split g into the part left and the rest, keeping the order of vertices in each. Return
+    //! the bipartite graph and a vector v where v[i_new]=i_old. Fail if an edge of g joins two
+    //! vertices of the same part.
+    if left.len() != g.len() {
+        return Err(GraphError::SizeMismatch { len: left.len(), expected: g.len() });
+    }
+    let map: Vec<usize> = (0..g.len()).filter(|&v| left[v])
+        .chain((0..g.len()).filter(|&v| !left[v])).collect();
+    let mut inverse = vec![0; g.len()];
+    for (i, &v) in map.iter().enumerate() {
+        inverse[v] = i;
+    }
+    let m = kernels::count_ones(left);
+    let mut b = new(m, g.len() - m);
+    for (i, &u) in map.iter().enumerate() {
+        for w in g.out_neighbor_iter(u) {
+            if left[u] == left[w] {
+                return Err(GraphError::SamePart(u, w));
+            }
+            if left[u] {
+                b.rows[i].set(inverse[w] - m, true);
+            }
+        }
+    }
+    Ok((b, map))
+}
+
+impl BipartiteGraph {
+    fn check(&self, v: usize) {
+        assert!(v < self.len(), "vertex {} out of bounds for graph of size {}", v, self.len());
+    }
+
+    fn cell(&self, u: usize, v: usize) -> Option<(usize, usize)> {
+        // biadjacency entry of the pair, or None if both are in the same part
+        let m = self.rows.len();
+        match (u < m, v < m) {
+            (true, false) => Some((u, v - m)),
+            (false, true) => Some((v, u - m)),
+            _ => None
+        }
+    }
+
+    fn set(&mut self, u: usize, v: usize, value: bool) -> Result<(), GraphError> {
+        self.check_vertex(u)?;
+        self.check_vertex(v)?;
+        match self.cell(u, v) {
+            Some((i, j)) => {
+                self.rows[i].set(j, value);
+                Ok(())
+            }
+            None => Err(GraphError::SamePart(u, v))
+        }
+    }
+
+    pub fn left_len(&self) -> usize {
+        //! Return size m of the left part.
+        self.rows.len()
+    }
+
+    pub fn right_len(&self) -> usize {
+        //! Return size n of the right part.
+        self.n
+    }
+
+    pub fn is_left(&self, v: usize) -> bool {
+        //! Decide whether v is in the left part.
+        self.check(v);
+        v < self.rows.len()
+    }
+
+    pub fn biadjacency(&self) -> &[BitVec] {
+        //! Return the m rows of the biadjacency matrix, each of length n.
+        &self.rows
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        //! Join u and v. Panics if they are in the same part.
+        if let Err(e) = self.set(u, v, true) {
+            panic!("{}", e);
+        }
+    }
+
+    pub fn try_add_edge(&mut self, u: usize, v: usize) -> Result<(), GraphError> {
+        //! Join u and v, or fail if either is out of bounds or both are in the same part.
+        self.set(u, v, true)
+    }
+
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        //! Disconnect u and v. Panics if they are in the same part.
+        if let Err(e) = self.set(u, v, false) {
+            panic!("{}", e);
+        }
+    }
+
+    pub fn to_graph(&self) -> Graph {
+        //! Expand self into a graph on m+n vertices with the same ids.
+        let m = self.rows.len();
+        let mut g = graph::new(m + self.n);
+        for (i, r) in self.rows.iter().enumerate() {
+            for j in kernels::ones(r) {
+                g.add_edge(i, m + j);
+            }
+        }
+        g
+    }
+
+    pub fn left_projection(&self) -> Graph {
+        //! Return the graph on the m left vertices where two are joined if they share a
+        //! neighbor.
+        project(&self.rows)
+    }
+
+    pub fn right_projection(&self) -> Graph {
+        //! Return the graph on the n right vertices where two are joined if they share a
+        //! neighbor. Vertex m+j becomes j.
+        let m = self.rows.len();
+        let cols: Vec<BitVec> = (0..self.n).map(|j| BitVec::from_fn(m, |i| self.rows[i][j]))
+            .collect();
+        project(&cols)
+    }
+}
+
+fn project(rows: &[BitVec]) -> Graph {
+    let mut g = graph::new(rows.len());
+    for (i, a) in rows.iter().enumerate() {
+        for (k, b) in rows.iter().enumerate().skip(i + 1) {
+            if !kernels::is_disjoint(a, b) {
+                g.add_edge(i, k);
+            }
+        }
+    }
+    g
+}
+
+impl GraphRef for BipartiteGraph {
+    fn len(&self) -> usize {
+        self.rows.len() + self.n
+    }
+    fn is_directed(&self) -> bool {
+        false
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.check(from);
+        self.check(to);
+        self.cell(from, to).is_some_and(|(i, j)| self.rows[i][j])
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.out_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        // a left row sits in the right columns and a right column in the left ones
+        self.check(id);
+        let m = self.rows.len();
+        let r = if id < m {
+            BitVec::from_fn(m + self.n, |w| w >= m && self.rows[id][w - m])
+        } else {
+            BitVec::from_fn(m + self.n, |w| w < m && self.rows[w][id - m])
+        };
+        Cow::Owned(r)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.check(id);
+        let m = self.rows.len();
+        if id < m {
+            Neighbors::shifted(&self.rows[id], m)
+        } else {
+            Neighbors::column(&self.rows, id - m)
+        }
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.out_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.check(id);
+        let m = self.rows.len();
+        if id < m {
+            kernels::count_ones(&self.rows[id])
+        } else {
+            self.rows.iter().filter(|r| r[id - m]).count()
+        }
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.out_degree(id)
+    }
+}
@@ -0,0 +1,190 @@
+//! Word-level operations on bit vectors that never allocate.
+//!
+//! Operands may have different lengths: any word past the end of a shorter operand is treated
+//! as all zeros, so a BitSet that has grown past the size of a graph row can be mixed freely
+//! with that row.
+
+use std::borrow::Cow;
+use super::{BitVec, BITS};
+
+fn word(v: &BitVec, i: usize) -> u64 {
+    v.storage.get(i).cloned().unwrap_or(0)
+}
+
+fn words(a: &BitVec, b: &BitVec) -> usize {
+    if a.storage.len() > b.storage.len() { a.storage.len() } else { b.storage.len() }
+}
+
+pub fn count_ones(a: &BitVec) -> usize {
+    //! Return number of set bits in a.
+    a.storage.iter().map(|w| w.count_ones() as usize).sum()
+}
+
+pub fn and_count(a: &BitVec, b: &BitVec) -> usize {
+    //! Return |a & b|.
+    a.storage.iter().zip(b.storage.iter()).map(|(x, y)| (x & y).count_ones() as usize).sum()
+}
+
+pub fn andnot_count(a: &BitVec, b: &BitVec) -> usize {
+    //! Return |a & !b|.
+    (0..a.storage.len()).map(|i| (a.storage[i] & !word(b, i)).count_ones() as usize).sum()
+}
+
+pub fn or_count(a: &BitVec, b: &BitVec) -> usize {
+    //! Return |a | b|.
+    (0..words(a, b)).map(|i| (word(a, i) | word(b, i)).count_ones() as usize).sum()
+}
+
+pub fn is_disjoint(a: &BitVec, b: &BitVec) -> bool {
+    //! Decide whether a & b is empty.
+    a.storage.iter().zip(b.storage.iter()).all(|(x, y)| x & y == 0)
+}
+
+pub fn is_subset(a: &BitVec, b: &BitVec) -> bool {
+    //! Decide whether every bit set in a is also set in b.
+    (0..a.storage.len()).all(|i| a.storage[i] & !word(b, i) == 0)
+}
+
+pub fn first_one(a: &BitVec) -> Option<usize> {
+    //! Return the index of the lowest set bit of a.
+    next_one(a, 0)
+}
+
+pub fn next_one(a: &BitVec, from: usize) -> Option<usize> {
+    //! Return the index of the lowest set bit of a that is at least from.
+    let mut i = from / BITS;
+    if i >= a.storage.len() {
+        return None;
+    }
+    let mut w = a.storage[i] & (!0 << (from % BITS));
+    loop {
+        if w != 0 {
+            return Some(i * BITS + w.trailing_zeros() as usize);
+        }
+        i += 1;
+        if i == a.storage.len() {
+            return None;
+        }
+        w = a.storage[i];
+    }
+}
+
+pub fn first_and(a: &BitVec, b: &BitVec) -> Option<usize> {
+    //! Return the index of the lowest bit set in both a and b.
+    a.storage.iter().zip(b.storage.iter()).enumerate()
+        .find(|&(_, (x, y))| x & y != 0)
+        .map(|(i, (x, y))| i * BITS + (x & y).trailing_zeros() as usize)
+}
+
+pub fn and3_count(a: &BitVec, b: &BitVec, c: &BitVec) -> usize {
+    //! Return |a & b & c| in a single pass.
+    a.storage.iter().zip(b.storage.iter()).zip(c.storage.iter())
+        .map(|((x, y), z)| (x & y & z).count_ones() as usize).sum()
+}
+
+pub fn and_count_rows(rows: &[&BitVec]) -> usize {
+    //! Return the number of bits set in every one of rows, in a single pass over the words.
+    //! An empty slice of rows has no bits in common.
+    if rows.is_empty() {
+        return 0;
+    }
+    let n = rows.iter().map(|r| r.storage.len()).min().unwrap_or(0);
+    (0..n).map(|i| rows.iter().fold(!0, |acc, r| acc & r.storage[i]).count_ones() as usize).sum()
+}
+
+pub fn first_disjoint<'a, I>(mask: &BitVec, rows: I) -> Option<usize>
+    where I: IntoIterator<Item = &'a BitVec> {
+    //! Return the position of the first row that shares no bit with mask.
+    rows.into_iter().position(|r| is_disjoint(mask, r))
+}
+
+pub fn all_subsets<'a, I>(mask: &BitVec, rows: I) -> bool
+    where I: IntoIterator<Item = &'a BitVec> {
+    //! Decide whether every row is a subset of mask.
+    rows.into_iter().all(|r| is_subset(r, mask))
+}
+
+pub fn mark_new<F>(seen: &mut BitVec, row: &BitVec, mut f: F) -> usize
+    where F: FnMut(usize) {
+    //! Call f on every bit of row that is not set in seen, in increasing order, and set it in
+    //! seen. Both vectors must have the same length. Return the number of bits marked.
+    assert_eq!(seen.len(), row.len(), "bit vectors must have equal length");
+    let mut marked = 0;
+    for (i, (s, &r)) in seen.storage.iter_mut().zip(row.storage.iter()).enumerate() {
+        let mut fresh = r & !*s;
+        *s |= fresh;
+        marked += fresh.count_ones() as usize;
+        while fresh != 0 {
+            f(i * BITS + fresh.trailing_zeros() as usize);
+            fresh &= fresh - 1;
+        }
+    }
+    marked
+}
+
+pub fn ones(a: &BitVec) -> Ones<'_> {
+    //! Iterate over the indices of set bits in a.
+    Ones { a: &a.storage, b: None, idx: 0, cur: a.storage.first().cloned().unwrap_or(0) }
+}
+
+pub fn ones_andnot<'a>(a: &'a BitVec, b: &'a BitVec) -> Ones<'a> {
+    //! Iterate over the indices of bits set in a but not in b.
+    let cur = word(a, 0) & !word(b, 0);
+    Ones { a: &a.storage, b: Some(&b.storage), idx: 0, cur }
+}
+
+pub fn into_ones(a: Cow<'_, BitVec>) -> IntoOnes<'_> {
+    //! Iterate over the indices of set bits in a, which may be borrowed or owned.
+    let cur = a.storage.first().cloned().unwrap_or(0);
+    IntoOnes { a, idx: 0, cur }
+}
+
+/// Iterator over set bits, one word at a time.
+pub struct Ones<'a> {
+    a: &'a [u64],
+    b: Option<&'a [u64]>,
+    idx: usize,
+    cur: u64,
+}
+
+impl<'a> Iterator for Ones<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.cur == 0 {
+            self.idx += 1;
+            if self.idx >= self.a.len() {
+                return None;
+            }
+            let mask = self.b.and_then(|b| b.get(self.idx)).cloned().unwrap_or(0);
+            self.cur = self.a[self.idx] & !mask;
+        }
+        let bit = self.cur.trailing_zeros() as usize;
+        self.cur &= self.cur - 1;
+        Some(self.idx * BITS + bit)
+    }
+}
+
+/// Iterator over set bits of a borrowed or owned vector, one word at a time.
+pub struct IntoOnes<'a> {
+    a: Cow<'a, BitVec>,
+    idx: usize,
+    cur: u64,
+}
+
+impl<'a> Iterator for IntoOnes<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.cur == 0 {
+            self.idx += 1;
+            if self.idx >= self.a.storage.len() {
+                return None;
+            }
+            self.cur = self.a.storage[self.idx];
+        }
+        let bit = self.cur.trailing_zeros() as usize;
+        self.cur &= self.cur - 1;
+        Some(self.idx * BITS + bit)
+    }
+}
@@ -0,0 +1,413 @@
+//! Word-based bit vector and bit set used as the rows of every graph.
+//!
+//! The API mirrors the old `std::collections::{BitVec, BitSet}` types that this crate was
+//! written against, so graph code reads the same as before.
+
+use std::cmp;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Index;
+
+pub mod kernels;
+
+const BITS: usize = 64;
+
+static TRUE: bool = true;
+static FALSE: bool = false;
+
+fn blocks_for(nbits: usize) -> usize {
+    nbits.div_ceil(BITS)
+}
+
+/// A fixed-length vector of bits, stored 64 to a word.
+/// Bits past len() in the last word are always zero.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVec {
+    storage: Vec<u64>,
+    nbits: usize,
+}
+
+impl BitVec {
+    pub fn new() -> BitVec {
+        //! Construct an empty bit vector.
+        BitVec { storage: Vec::new(), nbits: 0 }
+    }
+
+    pub fn with_capacity(nbits: usize) -> BitVec {
+        //! Construct an empty bit vector with room for nbits bits.
+        BitVec { storage: Vec::with_capacity(blocks_for(nbits)), nbits: 0 }
+    }
+
+    pub fn from_elem(nbits: usize, bit: bool) -> BitVec {
+        //! Construct a bit vector of length nbits with every bit set to bit.
+        let fill = if bit { !0 } else { 0 };
+        let mut v = BitVec { storage: vec![fill; blocks_for(nbits)], nbits };
+        v.fix_last_block();
+        v
+    }
+
+    pub fn from_fn<F>(nbits: usize, mut f: F) -> BitVec
+        where F: FnMut(usize) -> bool {
+        //! Construct a bit vector of length nbits where bit i is f(i).
+        let mut v = BitVec::from_elem(nbits, false);
+        for i in 0..nbits {
+            if f(i) {
+                v.set(i, true);
+            }
+        }
+        v
+    }
+
+    fn fix_last_block(&mut self) {
+        // zero the unused high bits of the last word
+        let extra = self.nbits % BITS;
+        if extra > 0 {
+            if let Some(last) = self.storage.last_mut() {
+                *last &= (1 << extra) - 1;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        //! Return number of bits in self.
+        self.nbits
+    }
+
+    pub fn is_empty(&self) -> bool {
+        //! Return whether self has no bits at all.
+        self.nbits == 0
+    }
+
+    pub fn get(&self, i: usize) -> Option<bool> {
+        //! Return bit i, or None if i is out of bounds.
+        if i >= self.nbits {
+            None
+        } else {
+            Some(self.storage[i / BITS] & (1 << (i % BITS)) != 0)
+        }
+    }
+
+    pub fn set(&mut self, i: usize, x: bool) {
+        //! Set bit i to x. Panics if i is out of bounds.
+        assert!(i < self.nbits, "index out of bounds: {} >= {}", i, self.nbits);
+        let w = i / BITS;
+        let flag = 1 << (i % BITS);
+        if x {
+            self.storage[w] |= flag;
+        } else {
+            self.storage[w] &= !flag;
+        }
+    }
+
+    pub fn set_all(&mut self) {
+        //! Set every bit to true.
+        for w in self.storage.iter_mut() {
+            *w = !0;
+        }
+        self.fix_last_block();
+    }
+
+    pub fn clear(&mut self) {
+        //! Set every bit to false. The length does not change.
+        for w in self.storage.iter_mut() {
+            *w = 0;
+        }
+    }
+
+    pub fn negate(&mut self) {
+        //! Flip every bit.
+        for w in self.storage.iter_mut() {
+            *w = !*w;
+        }
+        self.fix_last_block();
+    }
+
+    fn combine<F>(&mut self, other: &BitVec, mut op: F) -> bool
+        where F: FnMut(u64, u64) -> u64 {
+        assert_eq!(self.nbits, other.nbits, "bit vectors must have equal length");
+        let mut changed = false;
+        for (a, &b) in self.storage.iter_mut().zip(other.storage.iter()) {
+            let w = op(*a, b);
+            changed |= w != *a;
+            *a = w;
+        }
+        changed
+    }
+
+    pub fn union(&mut self, other: &BitVec) -> bool {
+        //! Set self to self | other. Return whether self changed.
+        //! Both vectors must have the same length.
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersect(&mut self, other: &BitVec) -> bool {
+        //! Set self to self & other. Return whether self changed.
+        //! Both vectors must have the same length.
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn difference(&mut self, other: &BitVec) -> bool {
+        //! Set self to self & !other. Return whether self changed.
+        //! Both vectors must have the same length.
+        self.combine(other, |a, b| a & !b)
+    }
+
+    pub fn any(&self) -> bool {
+        //! Return whether any bit is set.
+        self.storage.iter().any(|&w| w != 0)
+    }
+
+    pub fn none(&self) -> bool {
+        //! Return whether no bit is set.
+        !self.any()
+    }
+
+    pub fn all(&self) -> bool {
+        //! Return whether every bit is set.
+        self.iter().all(|b| b)
+    }
+
+    pub fn push(&mut self, bit: bool) {
+        //! Append a bit to the end of self.
+        if self.nbits.is_multiple_of(BITS) {
+            self.storage.push(0);
+        }
+        self.nbits += 1;
+        let i = self.nbits - 1;
+        self.set(i, bit);
+    }
+
+    pub fn pop(&mut self) -> Option<bool> {
+        //! Remove and return the last bit, or None if self is empty.
+        if self.nbits == 0 {
+            return None;
+        }
+        let bit = self[self.nbits - 1];
+        self.truncate(self.nbits - 1);
+        Some(bit)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        //! Shorten self to len bits. Does nothing if len >= self.len().
+        if len < self.nbits {
+            self.nbits = len;
+            self.storage.truncate(blocks_for(len));
+            self.fix_last_block();
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        //! Return number of bits self can hold without reallocating.
+        self.storage.capacity() * BITS
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        //! Make room for at least additional more bits.
+        let want = blocks_for(self.nbits + additional);
+        let have = self.storage.len();
+        if want > have {
+            self.storage.reserve(want - have);
+        }
+    }
+
+    pub fn swap_remove(&mut self, i: usize) -> bool {
+        //! Remove bit i and return it, moving the last bit into its place.
+        let bit = self[i];
+        let last = self.pop().unwrap();
+        if i < self.nbits {
+            self.set(i, last);
+        }
+        bit
+    }
+
+    pub fn grow(&mut self, n: usize, value: bool) {
+        //! Append n copies of value to the end of self.
+        for _ in 0..n {
+            self.push(value);
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        //! Iterate over every bit of self as a bool.
+        Iter { bits: self, idx: 0 }
+    }
+}
+
+impl Index<usize> for BitVec {
+    type Output = bool;
+
+    fn index(&self, i: usize) -> &bool {
+        match self.get(i) {
+            Some(true) => &TRUE,
+            Some(false) => &FALSE,
+            None => panic!("index out of bounds: {} >= {}", i, self.nbits),
+        }
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> BitVec {
+        let iter = iter.into_iter();
+        let mut v = BitVec::with_capacity(iter.size_hint().0);
+        for b in iter {
+            v.push(b);
+        }
+        v
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.iter() {
+            write!(f, "{}", if b { "1" } else { "0" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the bits of a BitVec.
+pub struct Iter<'a> {
+    bits: &'a BitVec,
+    idx: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let b = self.bits.get(self.idx);
+        if b.is_some() {
+            self.idx += 1;
+        }
+        b
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let rest = self.bits.len() - self.idx;
+        (rest, Some(rest))
+    }
+}
+
+/// A set of small integers backed by a BitVec, which grows as elements are inserted.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct BitSet {
+    bits: BitVec,
+}
+
+impl BitSet {
+    pub fn new() -> BitSet {
+        //! Construct an empty set.
+        BitSet { bits: BitVec::new() }
+    }
+
+    pub fn with_capacity(nbits: usize) -> BitSet {
+        //! Construct an empty set that can hold values below nbits without growing.
+        BitSet { bits: BitVec::from_elem(nbits, false) }
+    }
+
+    pub fn from_bit_vec(bits: BitVec) -> BitSet {
+        //! Construct a set containing every true index of bits.
+        BitSet { bits }
+    }
+
+    pub fn into_bit_vec(self) -> BitVec {
+        //! Consume self and return the underlying bit vector.
+        self.bits
+    }
+
+    pub fn get_ref(&self) -> &BitVec {
+        //! Borrow the underlying bit vector.
+        &self.bits
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        //! Test whether value is in self.
+        self.bits.get(value).unwrap_or(false)
+    }
+
+    pub fn insert(&mut self, value: usize) -> bool {
+        //! Add value to self. Return true if it was not already present.
+        if self.contains(value) {
+            return false;
+        }
+        if value >= self.bits.len() {
+            let grow = cmp::max(value + 1, self.bits.len() * 2) - self.bits.len();
+            self.bits.grow(grow, false);
+        }
+        self.bits.set(value, true);
+        true
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        //! Remove value from self. Return true if it was present.
+        if !self.contains(value) {
+            return false;
+        }
+        self.bits.set(value, false);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        //! Return number of elements in self.
+        kernels::count_ones(&self.bits)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        //! Return whether self has no elements.
+        self.bits.none()
+    }
+
+    pub fn clear(&mut self) {
+        //! Remove every element from self.
+        self.bits.clear();
+    }
+
+    fn match_len(&mut self, other: &BitSet) -> BitVec {
+        // pad self and a copy of other to a common length
+        let n = cmp::max(self.bits.len(), other.bits.len());
+        let len = self.bits.len();
+        self.bits.grow(n - len, false);
+        let mut o = other.bits.clone();
+        o.grow(n - other.bits.len(), false);
+        o
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        //! Add every element of other to self.
+        let o = self.match_len(other);
+        self.bits.union(&o);
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        //! Remove every element of self that is not in other.
+        let o = self.match_len(other);
+        self.bits.intersect(&o);
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        //! Remove every element of other from self.
+        let o = self.match_len(other);
+        self.bits.difference(&o);
+    }
+
+    pub fn iter(&self) -> kernels::Ones<'_> {
+        //! Iterate over the elements of self in increasing order.
+        kernels::ones(&self.bits)
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> BitSet {
+        let mut s = BitSet::new();
+        for v in iter {
+            s.insert(v);
+        }
+        s
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -1,22 +1,36 @@
 use std::io;
-use std::io::{BufRead, Read};
-use std::collections::{BitVec, HashMap, BitSet};
-use graph::Graph;
-use BitGraph;
+use std::io::Read;
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Index;
+use bitset::BitVec;
+use bitset::kernels;
+use graph;
+use matrix;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, NodeAttrs, EdgeAttrs};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DiGraph {
     /// from[i] is the in neighborhood of i.
     from: Vec<BitVec>,
     /// to[i] is the out neighborhood of i.
-    to: Vec<BitVec>
+    to: Vec<BitVec>,
+    /// Whether from[i][i] and to[i][i] may be set.
+    loops: LoopPolicy
 }
 
 pub fn new(size: usize) -> DiGraph {
     //! Construct new Graph with size number of vertices.
+    with_loops(size, LoopPolicy::default())
+}
+
+pub fn with_loops(size: usize, loops: LoopPolicy) -> DiGraph {
+    //! Construct new DiGraph with size number of vertices and the given loop policy.
     DiGraph {
-        from: vec![BitVec::from_elem(size, false); size],
-        to: vec![BitVec::from_elem(size, false); size]
+        from: matrix::new(size),
+        to: matrix::new(size),
+        loops
     }
 }
 
@@ -25,131 +39,214 @@ impl DiGraph {
         //! Transpose of self, aka reversed edges.
         DiGraph {
             from: self.to.clone(),
-            to: self.from.clone()
+            to: self.from.clone(),
+            loops: self.loops
         }
     }
-}
 
-impl BitGraph for DiGraph {
-    fn verify(&self) -> bool {
-        self.from.len() == self.to.len()
+    pub fn set_loop_policy(&mut self, loops: LoopPolicy) -> Result<(), GraphError> {
+        //! Change how self treats loops. Switching to Ignore drops existing loops, and switching
+        //! to Forbid fails if there are any.
+        match loops {
+            LoopPolicy::Allow => {}
+            LoopPolicy::Ignore => {
+                matrix::clear_diagonal(&mut self.from);
+                matrix::clear_diagonal(&mut self.to);
+            }
+            LoopPolicy::Forbid => if let Some(v) = matrix::has_diagonal(&self.to) {
+                return Err(GraphError::SelfLoop(v));
+            }
+        }
+        self.loops = loops;
+        Ok(())
     }
+
+    fn link(&mut self, fr: usize, to: usize) {
+        // add an arc created by an operation rather than asked for, dropping disallowed loops
+        if fr != to || self.loops == LoopPolicy::Allow {
+            self.from[to].set(fr, true);
+            self.to[fr].set(to, true);
+        }
+    }
+}
+
+impl GraphRef for DiGraph {
     fn len(&self) -> usize {
         self.from.len()
     }
+    fn is_directed(&self) -> bool {
+        true
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.loops
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.from[id])
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.to[id])
+    }
+}
+
+impl GraphMut for DiGraph {
     fn add_edge(&mut self, fr: usize, to: usize) {
-        self.from[to].set(fr, true);
-        self.to[fr].set(to, true);
+        //! Panics on a loop if loops are forbidden.
+        if let Err(e) = self.try_add_edge(fr, to) {
+            panic!("{}", e);
+        }
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.check_vertex(fr)?;
+        self.check_vertex(to)?;
+        if fr != to || self.loops.admit(fr)? {
+            self.from[to].set(fr, true);
+            self.to[fr].set(to, true);
+        }
+        Ok(())
     }
     fn remove_edge(&mut self, fr: usize, to: usize) {
         self.from[to].set(fr, false);
         self.to[fr].set(to, false);
     }
-    fn in_neighbors(&self, id: usize) -> &BitVec {
-        &self.from[id]
-    }
-    fn out_neighbors(&self, id: usize) -> &BitVec {
-        &self.to[id]
+    fn add_vertex(&mut self) -> usize {
+        matrix::add_vertex(&mut self.from);
+        matrix::add_vertex(&mut self.to)
     }
-    fn complement(&self) -> DiGraph {
-        DiGraph {
-            from: (&self.from as &Graph).complement(),
-            to: (&self.to as &Graph).complement()
-        }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        // the same relabeling applies to both matrices
+        matrix::remove_vertex(&mut self.from, v, mode);
+        matrix::remove_vertex(&mut self.to, v, mode)
     }
     fn induce(&mut self, vertices: &BitVec) {
-        (&mut self.from as &mut Graph).induce(vertices);
-        (&mut self.to as &mut Graph).induce(vertices);
-    }
-    fn compressed(&self) -> (DiGraph, Vec<usize>) {
-        let map = (0..self.len()).filter(|&idx| self.from[idx].any() ||
-                                         self.to[idx].any()).collect();
-
-        let (new_fr, new_to) = self.from.iter().zip(self.to.iter())
-            .filter(|&(f,t)| f.any() || t.any())
-            .map(|(x,y)| (x.clone(), y.clone()))
-            .unzip();
-        (DiGraph {
-            from: new_fr,
-            to: new_to
-        }, map)
+        matrix::induce(&mut self.from, vertices);
+        matrix::induce(&mut self.to, vertices);
     }
     fn contract(&mut self, e: (usize, usize)) {
         let (u, v) = e;
-        assert!(self.from[u][v]);
+        assert!(self.to[u][v]);
+        // detach v, then reattach its arcs to u; arcs between u and v and a loop at v become a
+        // loop at u if loops are allowed
         let from_v = self.from[v].clone();
         let to_v = self.to[v].clone();
-        self.from[u].union(&from_v);
-        self.to[u].union(&to_v);
-        for i in BitSet::from_bit_vec(to_v).iter() {
-            self.from[i].set(v, false);
-            self.from[i].set(u, true);
+        for i in kernels::ones(&to_v) {
+            self.remove_edge(v, i);
+        }
+        for i in kernels::ones(&from_v) {
+            self.remove_edge(i, v);
+        }
+        let merge = |i: usize| if i == v { u } else { i };
+        for i in kernels::ones(&to_v) {
+            self.link(u, merge(i));
         }
-        for i in BitSet::from_bit_vec(from_v).iter() {
-            self.to[i].set(v, false);
-            self.to[i].set(u, true);
+        for i in kernels::ones(&from_v) {
+            self.link(merge(i), u);
         }
+    }
+}
 
-        // clear the rows of v
-        self.to[v].set_all();
-        self.to[v].negate();
-        self.from[v].set_all();
-        self.from[v].negate();
+impl BitGraph for DiGraph {
+    fn verify(&self) -> bool {
+        //! Make sure both matrices are square and transposes of each other, with no loops
+        //! unless they are allowed.
+        let n = self.len();
+        self.from.len() == self.to.len() && matrix::is_square(&self.from) &&
+            matrix::is_square(&self.to) &&
+            (0..n).all(|i| (0..n).all(|j| self.from[i][j] == self.to[j][i])) &&
+            (self.loops == LoopPolicy::Allow || matrix::has_diagonal(&self.to).is_none())
+    }
+    fn complement(&self) -> DiGraph {
+        let keep = self.loops == LoopPolicy::Allow;
+        DiGraph {
+            from: matrix::complement(&self.from, keep),
+            to: matrix::complement(&self.to, keep),
+            loops: self.loops
+        }
+    }
+    fn compressed(&self) -> (DiGraph, Vec<usize>) {
+        let map: Vec<usize> = (0..self.len()).filter(|&idx| self.from[idx].any() ||
+                                                     self.to[idx].any()).collect();
+        (DiGraph {
+            from: matrix::select(&self.from, &map),
+            to: matrix::select(&self.to, &map),
+            loops: self.loops
+        }, map)
     }
     fn reordered(&self, order: &[usize]) -> Self {
         DiGraph {
-            from: (&self.from as &Graph).reordered(order),
-            to: (&self.to as &Graph).reordered(order)
+            from: matrix::select(&self.from, order),
+            to: matrix::select(&self.to, order),
+            loops: self.loops
         }
     }
-    fn serialize_dot(&self, node_attrs: Option<&HashMap<usize, HashMap<String, String>>>,
-                            edge_attrs: Option<&HashMap<(usize, usize), HashMap<String, String>>>)
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
         -> String
     {
-        // just like for graph, change -- to -> and graph to digraph
-        let mut out_lines = vec![format!("node [fontname=\"{}\",fontsize=\"{}\"]",
-                                            "sans-serif", "12")];
-        for i in (0..self.len()) {
-            let mut n_props = vec![format!("id={}", i)];
-            if let Some(attrs) = node_attrs {
-                n_props.push_all(&attrs[&i].iter().map(|(k,v)| format!("{}=\"{}\"", k,
-                                                                        v)).collect::<Vec<_>>());
-            }
-            out_lines.push(format!("{} [{}]", i, n_props.connect(",")));
-            for j in BitSet::from_bit_vec(self.out_neighbors(i).clone()).iter().filter(|&j| i<=j) {
-                let mut e_props = vec![format!("id=\"{},{}\"", i,j)];
-                if let Some(attrs) = edge_attrs {
-                    e_props.push_all(&attrs[&(i,j)].iter().map(|(k,v)| format!("{}=\"{}\"", k,
-                                                                                v)).collect::<Vec<_>>());
-                }
-                out_lines.push(format!("{} -> {} [{}]", i, j, e_props.connect(",")));
-            }
+        serialize_directed_dot(self, node_attrs, edge_attrs)
+    }
+}
 
+impl Index<(usize, usize)> for DiGraph {
+    type Output = bool;
+
+    fn index(&self, e: (usize, usize)) -> &bool {
+        //! Whether e is an arc; g[(u, v)] is g.has_edge(u, v).
+        &self.to[e.0][e.1]
+    }
+}
+
+impl Extend<(usize, usize)> for DiGraph {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, edges: I) {
+        //! Add each arc, first adding vertices up to the larger end if it is out of bounds.
+        for (u, v) in edges {
+            while self.len() <= u.max(v) {
+                self.add_vertex();
+            }
+            self.add_edge(u, v);
         }
-        format!("digraph {{\n{}\n}}", out_lines.connect("\n"))
     }
 }
 
-pub fn read_csv<R: Read>(reader: &mut io::BufReader<R>) -> Option<DiGraph> {
-    //! Read csv of 0's and 1's into a graph from given buffered reader.
-    //! Return Some(graph) if it passes validation, otherwise None.
-    let from = reader.lines().map(
-        // turn each row into iterator of ints
-        |r| r.unwrap_or(String::new()).split(",").map(
-            // parse each number into int and collect into vectors
-            |s| s.trim().parse().unwrap_or(0)).map(|v| v == 1)
-        .collect::<BitVec>()).collect::<Vec<_>>();
+impl FromIterator<(usize, usize)> for DiGraph {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(edges: I) -> DiGraph {
+        //! Collect arcs into a graph on 0..=max id with the default loop policy.
+        let mut g = new(0);
+        g.extend(edges);
+        g
+    }
+}
+
+impl fmt::Display for DiGraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //! Write one line per vertex: its id, a colon, and its out neighbors.
+        graph::fmt_adjacency(self, f)
+    }
+}
+
+pub fn serialize_directed_dot<G: GraphRef>(g: &G, node_attrs: Option<&NodeAttrs>,
+                                           edge_attrs: Option<&EdgeAttrs>) -> String {
+    //! Serialize any directed graph to DOT GraphViz format, writing every arc.
+    graph::write_dot(g, true, None, graph::dot_preamble(), node_attrs, edge_attrs)
+}
+
+pub fn read_csv<R: Read>(reader: &mut io::BufReader<R>) -> Result<DiGraph, GraphError> {
+    //! Read csv of 0's and 1's into a graph from given buffered reader, with the default loop
+    //! policy. Fail on malformed entries or ragged rows.
+    read_csv_with(reader, LoopPolicy::default())
+}
+
+pub fn read_csv_with<R: Read>(reader: &mut io::BufReader<R>, loops: LoopPolicy)
+    -> Result<DiGraph, GraphError> {
+    //! Read csv into a graph with the given loop policy. A 1 on the diagonal is kept, dropped or
+    //! rejected as the policy says.
+    let mut from = graph::read_matrix(reader)?;
+    graph::apply_loops(&mut from, loops)?;
     // to is transpose of from
-    let to = (0..from.len()).map(
-        |i| (0..from.len()).map(
-            |j| from[j][i]).collect()).collect();
+    let to = matrix::transpose(&from);
     let graph = DiGraph {
-        from: from,
-        to: to
+        from,
+        to,
+        loops
     };
-    if graph.verify() {
-        Some(graph)
-    } else {
-        None
-    }
+    assert!(graph.verify());
+    Ok(graph)
 }
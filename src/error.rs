@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Why a graph operation or parser failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GraphError {
+    /// A vertex id was not below the number of vertices.
+    OutOfBounds { vertex: usize, len: usize },
+    /// An operation required an edge that is not in the graph.
+    MissingEdge(usize, usize),
+    /// A loop at the vertex was given to a graph that forbids loops.
+    SelfLoop(usize),
+    /// Two vertices that must be in different parts of a bipartite graph were in the same one.
+    SamePart(usize, usize),
+    /// A tournament had the given number of arcs between a pair instead of exactly one.
+    ArcCount { pair: (usize, usize), arcs: usize },
+    /// An undirected graph had the edge (i, j) in only one direction.
+    Asymmetric(usize, usize),
+    /// Row row of a matrix had len entries where expected were needed.
+    RaggedRow { row: usize, len: usize, expected: usize },
+    /// A vertex set or ordering had len entries for a graph of size expected.
+    SizeMismatch { len: usize, expected: usize },
+    /// Two vertices were given the same label.
+    DuplicateLabel(String),
+    /// No vertex has the label.
+    UnknownLabel(String),
+    /// No checkpoint has the name.
+    UnknownCheckpoint(String),
+    /// Input could not be parsed; line counts from 1.
+    Parse { line: usize, msg: String },
+    /// Reading the input failed.
+    Io(String)
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphError::OutOfBounds { vertex, len } =>
+                write!(f, "vertex {} out of bounds for graph of size {}", vertex, len),
+            GraphError::MissingEdge(u, v) => write!(f, "no edge from {} to {}", u, v),
+            GraphError::SelfLoop(v) => write!(f, "loop at {} is forbidden", v),
+            GraphError::SamePart(u, v) => write!(f, "{} and {} are in the same part", u, v),
+            GraphError::ArcCount { pair: (u, v), arcs } =>
+                write!(f, "{} arcs between {} and {}, expected 1", arcs, u, v),
+            GraphError::Asymmetric(i, j) =>
+                write!(f, "matrix is not symmetric at ({}, {})", i, j),
+            GraphError::RaggedRow { row, len, expected } =>
+                write!(f, "row {} has {} entries, expected {}", row, len, expected),
+            GraphError::SizeMismatch { len, expected } =>
+                write!(f, "got {} entries for a graph of size {}", len, expected),
+            GraphError::DuplicateLabel(ref l) => write!(f, "label {:?} is used twice", l),
+            GraphError::UnknownLabel(ref l) => write!(f, "no vertex is labeled {:?}", l),
+            GraphError::UnknownCheckpoint(ref c) => write!(f, "no checkpoint is named {:?}", c),
+            GraphError::Parse { line, ref msg } => write!(f, "line {}: {}", line, msg),
+            GraphError::Io(ref msg) => write!(f, "read failed: {}", msg)
+        }
+    }
+}
+
+impl Error for GraphError {}
+
+impl From<io::Error> for GraphError {
+    fn from(e: io::Error) -> GraphError {
+        GraphError::Io(e.to_string())
+    }
+}
@@ -0,0 +1,101 @@
+//! The DIMACS graph format of the clique and coloring challenges (.col, .clq): comment lines
+//! starting with c, one problem line p edge n m, then one e u v line per edge with vertices
+//! counted from 1.
+
+use std::io::{self, BufRead, Read, Write};
+use digraph::{self, DiGraph};
+use graph::{self, Graph};
+use {GraphRef, GraphMut, GraphError};
+use super::dense_size;
+
+pub fn read_dimacs<R: Read>(reader: &mut io::BufReader<R>)
+    -> Result<(Graph, Vec<String>), GraphError> {
+    //! Read a DIMACS file into an undirected graph, returning it with the text of its comment
+    //! lines in order. Loops are dropped, as under the default loop policy, and repeated
+    //! edges collapse into one. A problem line with more than formats::DENSE_LIMIT vertices is
+    //! a Parse error.
+    read(reader, graph::new)
+}
+
+pub fn read_dimacs_directed<R: Read>(reader: &mut io::BufReader<R>)
+    -> Result<(DiGraph, Vec<String>), GraphError> {
+    //! Read a DIMACS file into a directed graph, taking e u v as the arc from u to v.
+    read(reader, digraph::new)
+}
+
+pub fn write_dimacs<G: GraphRef, W: Write>(g: &G, comments: &[String], writer: &mut W)
+    -> Result<(), GraphError> {
+    //! Write comments as c lines, then g in DIMACS form. An undirected edge is written once,
+    //! with its smaller end first.
+    for c in comments.iter() {
+        if c.is_empty() {
+            writeln!(writer, "c")?;
+        } else {
+            writeln!(writer, "c {}", c)?;
+        }
+    }
+    writeln!(writer, "p edge {} {}", g.len(), g.edge_count())?;
+    for (u, v) in g.edges() {
+        writeln!(writer, "e {} {}", u + 1, v + 1)?;
+    }
+    Ok(())
+}
+
+fn read<R, G, F>(reader: &mut io::BufReader<R>, make: F) -> Result<(G, Vec<String>), GraphError>
+    where R: Read, G: GraphMut, F: Fn(usize) -> G
+{
+    let mut graph = None;
+    let mut comments = Vec::new();
+    let mut last = 1;
+    for (i, line) in reader.lines().enumerate() {
+        last = i + 1;
+        let line = line?;
+        let err = |msg: String| GraphError::Parse { line: i + 1, msg };
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.first() {
+            None => {}
+            Some(&"c") => {
+                // keep the comment as written, less the marker and the space after it
+                let rest = &line.trim_start()[1..];
+                comments.push(rest.strip_prefix(' ').unwrap_or(rest).to_string());
+            }
+            Some(&"p") => {
+                if graph.is_some() {
+                    return Err(err("second problem line".to_string()));
+                }
+                if fields.len() != 4 || (fields[1] != "edge" && fields[1] != "col") {
+                    return Err(err(format!("expected 'p edge n m', got {:?}", line.trim())));
+                }
+                // check the sizes before the matrix is allocated from them
+                let n = number(fields[2]).and_then(dense_size).map_err(&err)?;
+                let m = number(fields[3]).map_err(&err)?;
+                if m > n * n {
+                    return Err(err(format!("{} edges, more than {} vertices can have", m, n)));
+                }
+                graph = Some(make(n));
+            }
+            Some(&"e") => {
+                let g = graph.as_mut()
+                    .ok_or_else(|| err("edge before problem line".to_string()))?;
+                if fields.len() != 3 {
+                    return Err(err(format!("expected 'e u v', got {:?}", line.trim())));
+                }
+                let (u, v) = (number(fields[1]).map_err(&err)?, number(fields[2]).map_err(&err)?);
+                let n = g.len();
+                if let Some(&x) = [u, v].iter().find(|&&x| x == 0 || x > n) {
+                    return Err(err(format!("vertex {} is outside 1 to {}", x, n)));
+                }
+                g.add_edge(u - 1, v - 1);
+            }
+            Some(&kind) => return Err(err(format!("unknown line type {:?}", kind)))
+        }
+    }
+    match graph {
+        Some(g) => Ok((g, comments)),
+        None => Err(GraphError::Parse { line: last, msg: "no problem line".to_string() })
+    }
+}
+
+fn number(s: &str) -> Result<usize, String> {
+    s.parse().map_err(|e| format!("{:?}: {}", s, e))
+}
@@ -0,0 +1,476 @@
+//! A reader for the DOT language of GraphViz, so graphs written by serialize_dot, or drawn by
+//! hand, can be read back. Covers strict, graph and digraph headers, node, edge and attribute
+//! statements, edge chains, attribute lists and subgraphs; ports are accepted and ignored.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use digraph::{self, DiGraph};
+use graph::{self, Graph};
+use {GraphMut, GraphError, LoopPolicy, NodeAttrs, EdgeAttrs};
+
+/// Attributes by name.
+pub type Attrs = HashMap<String, String>;
+
+/// A graph read from DOT, with its attributes in the shape serialize_dot takes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dot<G> {
+    pub graph: G,
+    /// names[v] is the DOT name of v.
+    pub names: Vec<String>,
+    /// Attributes of the graph itself, from graph [...] and a=b statements at the top level.
+    pub graph_attrs: Attrs,
+    /// Attributes of each node that has any, node defaults in scope included.
+    pub node_attrs: NodeAttrs,
+    /// Attributes of each edge that has any, keyed by (min, max) in an undirected graph.
+    pub edge_attrs: EdgeAttrs
+}
+
+pub fn parse_dot(s: &str) -> Result<Dot<Graph>, GraphError> {
+    //! Parse an undirected DOT graph. If the node names are exactly the ids 0 to n-1, as
+    //! serialize_dot writes them, names are taken as ids; otherwise vertices are numbered in
+    //! order of first appearance, so names such as 5 and 1000 make two vertices, not 1001. The
+    //! id attribute serialize_dot writes is not collected. Loops are dropped, as under the
+    //! default loop policy; parse_dot_with keeps them.
+    parse_dot_with(s, LoopPolicy::default())
+}
+
+pub fn parse_dot_with(s: &str, loops: LoopPolicy) -> Result<Dot<Graph>, GraphError> {
+    //! Parse an undirected DOT graph into a graph with the given loop policy, so the loops of a
+    //! graph with LoopPolicy::Allow survive serialize_dot and back. Fail on a loop under Forbid.
+    parse(s, false, |n| graph::with_loops(n, loops))
+}
+
+pub fn parse_dot_directed(s: &str) -> Result<Dot<DiGraph>, GraphError> {
+    //! Parse a directed DOT graph, as parse_dot does.
+    parse_dot_directed_with(s, LoopPolicy::default())
+}
+
+pub fn parse_dot_directed_with(s: &str, loops: LoopPolicy) -> Result<Dot<DiGraph>, GraphError> {
+    //! Parse a directed DOT graph with the given loop policy, as parse_dot_with does.
+    parse(s, true, |n| digraph::with_loops(n, loops))
+}
+
+pub fn read_dot<R: Read>(reader: &mut io::BufReader<R>) -> Result<Dot<Graph>, GraphError> {
+    //! Read all of reader and parse it with parse_dot.
+    let mut s = String::new();
+    reader.read_to_string(&mut s)?;
+    parse_dot(&s)
+}
+
+pub fn read_dot_directed<R: Read>(reader: &mut io::BufReader<R>)
+    -> Result<Dot<DiGraph>, GraphError> {
+    //! Read all of reader and parse it with parse_dot_directed.
+    let mut s = String::new();
+    reader.read_to_string(&mut s)?;
+    parse_dot_directed(&s)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    /// An identifier, numeral or string; quoted ones are never keywords.
+    Id(String, bool),
+    Sym(&'static str)
+}
+
+struct Token {
+    tok: Tok,
+    line: usize
+}
+
+fn lex(s: &str) -> Result<Vec<Token>, GraphError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    let mut line = 1;
+    let mut line_start = true;
+    let err = |line, msg: String| GraphError::Parse { line, msg };
+    while let Some(c) = chars.next() {
+        let start = line;
+        match c {
+            '\n' => {
+                line += 1;
+                line_start = true;
+                continue;
+            }
+            _ if c.is_whitespace() => continue,
+            // lines starting with # are preprocessor output and skipped
+            '#' if line_start => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            prev = c;
+                        }
+                        None => return Err(err(start, "unterminated comment".to_string()))
+                    }
+                }
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' | '+' => {
+                let sym = ["{", "}", "[", "]", ";", ",", "=", ":", "+"].iter()
+                    .find(|s| s.starts_with(c)).unwrap();
+                tokens.push(Token { tok: Tok::Sym(sym), line });
+            }
+            '-' if chars.peek() == Some(&'-') || chars.peek() == Some(&'>') => {
+                let op = if chars.next() == Some('-') { "--" } else { "->" };
+                tokens.push(Token { tok: Tok::Sym(op), line });
+            }
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(e @ '"') | Some(e @ '\\') => value.push(e),
+                            // a backslash before a line break continues the string
+                            Some('\n') => line += 1,
+                            Some(e) => {
+                                value.push('\\');
+                                value.push(e);
+                            }
+                            None => return Err(err(start, "unterminated string".to_string()))
+                        },
+                        Some(c) => {
+                            line += (c == '\n') as usize;
+                            value.push(c);
+                        }
+                        None => return Err(err(start, "unterminated string".to_string()))
+                    }
+                }
+                tokens.push(Token { tok: Tok::Id(value, true), line: start });
+            }
+            '<' => {
+                // an HTML string runs to the matching >
+                let mut value = String::new();
+                let mut depth = 1;
+                loop {
+                    match chars.next() {
+                        Some('>') if depth == 1 => break,
+                        Some(c) => {
+                            depth += (c == '<') as usize;
+                            depth -= (c == '>') as usize;
+                            line += (c == '\n') as usize;
+                            value.push(c);
+                        }
+                        None => return Err(err(start, "unterminated HTML string".to_string()))
+                    }
+                }
+                tokens.push(Token { tok: Tok::Id(value, true), line: start });
+            }
+            _ if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut value = c.to_string();
+                while let Some(&d) = chars.peek() {
+                    if d.is_alphanumeric() || d == '_' || d == '.' {
+                        value.push(d);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token { tok: Tok::Id(value, false), line });
+            }
+            _ => return Err(err(line, format!("unexpected character {:?}", c)))
+        }
+        line_start = false;
+    }
+    Ok(tokens)
+}
+
+/// Default attributes in force for new nodes and edges.
+#[derive(Clone, Default)]
+struct Scope {
+    node: Attrs,
+    edge: Attrs
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    directed: bool,
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    graph_attrs: Attrs,
+    /// nodes[i] holds the attributes of the i-th node named.
+    nodes: Vec<Attrs>,
+    /// Every edge between ith-named nodes, with its attributes, in file order.
+    edges: Vec<(usize, usize, Attrs)>
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens.get(self.pos).or_else(|| self.tokens.last()).map_or(1, |t| t.line)
+    }
+
+    fn error<T>(&self, msg: String) -> Result<T, GraphError> {
+        Err(GraphError::Parse { line: self.line(), msg })
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn peek_sym(&self, sym: &str) -> bool {
+        self.peek_sym_at(0, sym)
+    }
+
+    fn peek_sym_at(&self, ahead: usize, sym: &str) -> bool {
+        match self.tokens.get(self.pos + ahead) {
+            Some(&Token { tok: Tok::Sym(s), .. }) => s == sym,
+            _ => false
+        }
+    }
+
+    fn peek_keyword(&self, kw: &str) -> bool {
+        match self.peek() {
+            Some(&Tok::Id(ref s, false)) => s.eq_ignore_ascii_case(kw),
+            _ => false
+        }
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), GraphError> {
+        if self.peek_sym(sym) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected {:?}, found {}", sym, self.found()))
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(Tok::Id(s, _)) => format!("{:?}", s),
+            Some(&Tok::Sym(s)) => format!("{:?}", s),
+            None => "end of input".to_string()
+        }
+    }
+
+    fn id(&mut self) -> Result<String, GraphError> {
+        // an ID, with quoted strings joined by +
+        let mut value = match self.peek() {
+            Some(&Tok::Id(ref s, quoted)) if quoted || !is_keyword(s) => s.clone(),
+            _ => return self.error(format!("expected an ID, found {}", self.found()))
+        };
+        self.pos += 1;
+        while self.peek_sym("+") {
+            match self.tokens.get(self.pos + 1).map(|t| &t.tok) {
+                Some(&Tok::Id(ref s, true)) => value.push_str(s),
+                _ => return self.error("expected a string after '+'".to_string())
+            }
+            self.pos += 2;
+        }
+        Ok(value)
+    }
+
+    fn graph(&mut self) -> Result<(), GraphError> {
+        if self.peek_keyword("strict") {
+            self.pos += 1;
+        }
+        let directed = if self.peek_keyword("digraph") {
+            true
+        } else if self.peek_keyword("graph") {
+            false
+        } else {
+            return self.error(format!("expected graph or digraph, found {}", self.found()));
+        };
+        if directed != self.directed {
+            let want = if self.directed { "digraph" } else { "graph" };
+            return self.error(format!("expected {}, found {}", want, self.found()));
+        }
+        self.pos += 1;
+        if !self.peek_sym("{") {
+            self.id()?;
+        }
+        self.expect("{")?;
+        self.stmts(&mut Scope::default(), &mut Vec::new(), true)?;
+        self.expect("}")?;
+        if self.peek().is_some() {
+            return self.error(format!("expected end of input, found {}", self.found()));
+        }
+        Ok(())
+    }
+
+    fn stmts(&mut self, scope: &mut Scope, members: &mut Vec<usize>, top: bool)
+        -> Result<(), GraphError> {
+        // statements up to the closing brace, collecting the nodes they mention in members
+        while self.peek().is_some() && !self.peek_sym("}") {
+            self.stmt(scope, members, top)?;
+            if self.peek_sym(";") {
+                self.pos += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, scope: &mut Scope, members: &mut Vec<usize>, top: bool)
+        -> Result<(), GraphError> {
+        for kw in ["graph", "node", "edge"].iter() {
+            if self.peek_keyword(kw) && self.peek_sym_at(1, "[") {
+                self.pos += 1;
+                let attrs = self.attr_lists()?;
+                match *kw {
+                    "graph" if top => self.graph_attrs.extend(attrs),
+                    "graph" => {}
+                    "node" => scope.node.extend(attrs),
+                    _ => scope.edge.extend(attrs)
+                }
+                return Ok(());
+            }
+        }
+        if self.peek_sym_at(1, "=") {
+            let k = self.id()?;
+            self.expect("=")?;
+            let v = self.id()?;
+            if top {
+                self.graph_attrs.insert(k, v);
+            }
+            return Ok(());
+        }
+        let (first, is_node) = self.endpoint(scope)?;
+        members.extend(first.iter().cloned());
+        let op = if self.directed { "->" } else { "--" };
+        if !self.peek_sym("--") && !self.peek_sym("->") {
+            if is_node {
+                let attrs = if self.peek_sym("[") { self.attr_lists()? } else { Attrs::new() };
+                self.nodes[first[0]].extend(attrs);
+            }
+            return Ok(());
+        }
+        let mut chain = vec![first];
+        while self.peek_sym("--") || self.peek_sym("->") {
+            if !self.peek_sym(op) {
+                return self.error(format!("expected {:?}, found {}", op, self.found()));
+            }
+            self.pos += 1;
+            let (next, _) = self.endpoint(scope)?;
+            members.extend(next.iter().cloned());
+            chain.push(next);
+        }
+        let mut attrs = scope.edge.clone();
+        if self.peek_sym("[") {
+            attrs.extend(self.attr_lists()?);
+        }
+        for pair in chain.windows(2) {
+            for &u in pair[0].iter() {
+                for &v in pair[1].iter() {
+                    self.edges.push((u, v, attrs.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn endpoint(&mut self, scope: &Scope) -> Result<(Vec<usize>, bool), GraphError> {
+        // the nodes of a node id or a subgraph, and whether it was a node id
+        if self.peek_keyword("subgraph") || self.peek_sym("{") {
+            if self.peek_keyword("subgraph") {
+                self.pos += 1;
+                if !self.peek_sym("{") {
+                    self.id()?;
+                }
+            }
+            self.expect("{")?;
+            let mut members = Vec::new();
+            self.stmts(&mut scope.clone(), &mut members, false)?;
+            self.expect("}")?;
+            members.sort_unstable();
+            members.dedup();
+            return Ok((members, false));
+        }
+        let name = self.id()?;
+        // a port names a point on the node and does not change the graph
+        while self.peek_sym(":") {
+            self.pos += 1;
+            self.id()?;
+        }
+        let v = match self.index.get(&name) {
+            Some(&v) => v,
+            None => {
+                let v = self.names.len();
+                self.index.insert(name.clone(), v);
+                self.names.push(name);
+                self.nodes.push(scope.node.clone());
+                v
+            }
+        };
+        Ok((vec![v], true))
+    }
+
+    fn attr_lists(&mut self) -> Result<Attrs, GraphError> {
+        let mut attrs = Attrs::new();
+        while self.peek_sym("[") {
+            self.pos += 1;
+            while !self.peek_sym("]") {
+                let k = self.id()?;
+                self.expect("=")?;
+                let v = self.id()?;
+                attrs.insert(k, v);
+                if self.peek_sym(",") || self.peek_sym(";") {
+                    self.pos += 1;
+                }
+            }
+            self.pos += 1;
+        }
+        Ok(attrs)
+    }
+}
+
+fn is_keyword(s: &str) -> bool {
+    ["strict", "graph", "digraph", "subgraph", "node", "edge"].iter()
+        .any(|kw| s.eq_ignore_ascii_case(kw))
+}
+
+fn parse<G, F>(s: &str, directed: bool, make: F) -> Result<Dot<G>, GraphError>
+    where G: GraphMut, F: FnOnce(usize) -> G
+{
+    let mut p = Parser {
+        tokens: lex(s)?,
+        pos: 0,
+        directed,
+        names: Vec::new(),
+        index: HashMap::new(),
+        graph_attrs: Attrs::new(),
+        nodes: Vec::new(),
+        edges: Vec::new()
+    };
+    p.graph()?;
+    // names that are plain vertex ids covering 0 to n-1 are kept as ids; names are distinct,
+    // so n ids below n cover them all
+    let n = p.names.len();
+    let ids: Option<Vec<usize>> = p.names.iter()
+        .map(|s| s.parse::<usize>().ok().filter(|&v| v < n && v.to_string() == *s)).collect();
+    let (ids, names) = match ids {
+        Some(ids) => (ids, (0..n).map(|v| v.to_string()).collect()),
+        None => ((0..n).collect(), p.names)
+    };
+    let mut graph = make(names.len());
+    let mut node_attrs = NodeAttrs::new();
+    for (i, mut attrs) in p.nodes.into_iter().enumerate() {
+        attrs.remove("id");
+        if !attrs.is_empty() {
+            node_attrs.insert(ids[i], attrs);
+        }
+    }
+    let mut edge_attrs = EdgeAttrs::new();
+    for (u, v, mut attrs) in p.edges {
+        let (u, v) = (ids[u], ids[v]);
+        graph.try_add_edge(u, v)?;
+        attrs.remove("id");
+        // a loop the graph drops takes its attributes with it
+        if !attrs.is_empty() && graph.has_edge(u, v) {
+            let e = if directed || u <= v { (u, v) } else { (v, u) };
+            edge_attrs.entry(e).or_default().extend(attrs);
+        }
+    }
+    Ok(Dot { graph, names, graph_attrs: p.graph_attrs, node_attrs, edge_attrs })
+}
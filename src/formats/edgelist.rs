@@ -0,0 +1,201 @@
+//! Sparse text formats: edge lists of one u v [weight] line per edge, and adjacency lists of one
+//! v: w1 w2 ... line per vertex. Fields are separated by whitespace or commas, and # starts a
+//! comment running to the end of the line. The _into readers build any GraphMut, so large
+//! sparse lists can be read into a hybrid::HybridGraph, up to a number of vertices the caller
+//! chooses; the others build a dense graph and refuse lists with more than DENSE_LIMIT vertices.
+
+use std::io::{self, BufRead, Read, Write};
+use std::fmt::Display;
+use digraph::{self, DiGraph};
+use graph::{self, Graph};
+use weighted::WeightedGraph;
+use {GraphRef, GraphMut, BitGraph, GraphError};
+use super::dense_size;
+
+/// How an edge or adjacency list is read.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    /// Number of vertices, or None for one more than the largest id in the file.
+    pub size: Option<usize>,
+    /// Whether the first line holding anything but a comment names the columns and is skipped.
+    pub header: bool,
+    /// Whether the third field of each edge list line is parsed as the weight of the edge.
+    /// Otherwise fields past the second are skipped. Adjacency lists have no weights.
+    pub weights: bool
+}
+
+/// An edge read from a list, with its weight if weights were collected.
+pub type ListEdge = (usize, usize, Option<f64>);
+
+pub fn read_edge_list<R: Read>(reader: &mut io::BufReader<R>, opts: &ListOptions)
+    -> Result<(Graph, Vec<ListEdge>), GraphError> {
+    //! Read an edge list into an undirected graph, returning it with the edges in file order.
+    //! Loops are dropped, as under the default loop policy, and repeated edges collapse into
+    //! one. Use read_edge_list_into with graph::with_loops to keep them.
+    let (n, edges) = read_edges(reader, opts, false, &dense_size)?;
+    Ok((build(graph::new(n), &edges)?, edges))
+}
+
+pub fn read_edge_list_directed<R: Read>(reader: &mut io::BufReader<R>, opts: &ListOptions)
+    -> Result<(DiGraph, Vec<ListEdge>), GraphError> {
+    //! Read an edge list into a directed graph, taking u v as the arc from u to v.
+    let (n, edges) = read_edges(reader, opts, false, &dense_size)?;
+    Ok((build(digraph::new(n), &edges)?, edges))
+}
+
+pub fn read_edge_list_into<R, G, F>(reader: &mut io::BufReader<R>, opts: &ListOptions,
+                                    limit: usize, make: F)
+    -> Result<(G, Vec<ListEdge>), GraphError>
+    where R: Read, G: GraphMut, F: FnOnce(usize) -> G
+{
+    //! Read an edge list into the graph make returns for the number of vertices, such as
+    //! hybrid::new or hybrid::new_directed. A list needing more than limit vertices is a Parse
+    //! error, so a stray large id cannot ask make for a graph that does not fit in memory.
+    //! Edges make rejects, such as loops under LoopPolicy::Forbid, are returned as errors.
+    let (n, edges) = read_edges(reader, opts, false, &|n| within(n, limit))?;
+    Ok((build(make(n), &edges)?, edges))
+}
+
+pub fn read_adjacency_list<R: Read>(reader: &mut io::BufReader<R>, opts: &ListOptions)
+    -> Result<Graph, GraphError> {
+    //! Read an adjacency list into an undirected graph. The colon after the first id is
+    //! optional, and an edge listed under either end, or both, is added once.
+    let (n, edges) = read_edges(reader, opts, true, &dense_size)?;
+    build(graph::new(n), &edges)
+}
+
+pub fn read_adjacency_list_directed<R: Read>(reader: &mut io::BufReader<R>, opts: &ListOptions)
+    -> Result<DiGraph, GraphError> {
+    //! Read an adjacency list into a directed graph, taking v: w as the arc from v to w.
+    let (n, edges) = read_edges(reader, opts, true, &dense_size)?;
+    build(digraph::new(n), &edges)
+}
+
+pub fn read_adjacency_list_into<R, G, F>(reader: &mut io::BufReader<R>, opts: &ListOptions,
+                                         limit: usize, make: F) -> Result<G, GraphError>
+    where R: Read, G: GraphMut, F: FnOnce(usize) -> G
+{
+    //! Read an adjacency list into the graph make returns for the number of vertices, with the
+    //! limit of read_edge_list_into.
+    let (n, edges) = read_edges(reader, opts, true, &|n| within(n, limit))?;
+    build(make(n), &edges)
+}
+
+pub fn write_edge_list<G: GraphRef, W: Write>(g: &G, writer: &mut W) -> Result<(), GraphError> {
+    //! Write one u v line per edge, undirected edges once with the smaller end first. Trailing
+    //! isolated vertices are not recorded, so pass the size when reading such a graph back.
+    for (u, v) in g.edges() {
+        writeln!(writer, "{} {}", u, v)?;
+    }
+    Ok(())
+}
+
+pub fn write_weighted_edge_list<G, V, W>(g: &WeightedGraph<G, V>, writer: &mut W)
+    -> Result<(), GraphError>
+    where G: BitGraph, V: Display, W: Write
+{
+    //! Write one u v weight line per edge, in the order of WeightedGraph::weighted_edges.
+    for (u, v, w) in g.weighted_edges() {
+        writeln!(writer, "{} {} {}", u, v, w)?;
+    }
+    Ok(())
+}
+
+pub fn write_adjacency_list<G: GraphRef, W: Write>(g: &G, writer: &mut W)
+    -> Result<(), GraphError> {
+    //! Write one v: w1 w2 ... line per vertex listing its out neighbors, as Display does for
+    //! Graph and DiGraph. Every vertex gets a line, so the size is kept.
+    for v in 0..g.len() {
+        write!(writer, "{}:", v)?;
+        for w in g.out_neighbor_iter(v) {
+            write!(writer, " {}", w)?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+pub fn build<G: GraphMut>(mut g: G, edges: &[ListEdge]) -> Result<G, GraphError> {
+    //! Add edges to g with try_add_edge and return it, for building another graph from the
+    //! edges a reader returned. Fail at the first edge g rejects.
+    for &(u, v, _) in edges.iter() {
+        g.try_add_edge(u, v)?;
+    }
+    Ok(g)
+}
+
+fn within(n: usize, limit: usize) -> Result<usize, String> {
+    if n <= limit {
+        Ok(n)
+    } else {
+        Err(format!("{} vertices, more than the limit of {}", n, limit))
+    }
+}
+
+fn read_edges<R: Read>(reader: &mut io::BufReader<R>, opts: &ListOptions, adjacency: bool,
+                      check: &dyn Fn(usize) -> Result<usize, String>)
+    -> Result<(usize, Vec<ListEdge>), GraphError> {
+    // collect the edges first, since the size may only be known at the end; check bounds the
+    // number of vertices each id needs before anything is allocated
+    let mut edges = Vec::new();
+    let mut n = 0;
+    let mut header = opts.header;
+    let mut last = 1;
+    for (i, line) in reader.lines().enumerate() {
+        last = i + 1;
+        let line = line?;
+        let err = |msg: String| GraphError::Parse { line: i + 1, msg };
+        let content = line.split('#').next().unwrap();
+        let fields: Vec<&str> = content.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|f| !f.is_empty()).collect();
+        if fields.is_empty() {
+            continue;
+        }
+        if header {
+            header = false;
+            continue;
+        }
+        let id = |f: &str| -> Result<usize, GraphError> {
+            let v = f.parse::<usize>().map_err(|e| err(format!("{:?}: {}", f, e)))?;
+            match opts.size {
+                Some(size) if v >= size =>
+                    Err(err(format!("vertex {} out of bounds for graph of size {}", v, size))),
+                _ => {
+                    let need = v.checked_add(1)
+                        .ok_or_else(|| err(format!("vertex {} too large", v)))?;
+                    check(need).map_err(&err)?;
+                    Ok(v)
+                }
+            }
+        };
+        if adjacency {
+            let v = id(fields[0].trim_end_matches(':'))?;
+            n = n.max(v + 1);
+            for f in fields[1..].iter().filter(|&&f| f != ":") {
+                let w = id(f)?;
+                n = n.max(w + 1);
+                edges.push((v, w, None));
+            }
+            continue;
+        }
+        let want = if opts.weights { 3 } else { 2 };
+        if fields.len() < want {
+            return Err(err(format!("{} fields, expected at least {}", fields.len(), want)));
+        }
+        let (u, v) = (id(fields[0])?, id(fields[1])?);
+        let w = if opts.weights {
+            Some(fields[2].parse::<f64>().map_err(|e| err(format!("{:?}: {}", fields[2], e)))?)
+        } else {
+            None
+        };
+        n = n.max(u.max(v) + 1);
+        edges.push((u, v, w));
+    }
+    match opts.size {
+        Some(size) => match check(size) {
+            Ok(size) => Ok((size, edges)),
+            Err(msg) => Err(GraphError::Parse { line: last, msg })
+        },
+        None => Ok((n, edges))
+    }
+}
@@ -0,0 +1,332 @@
+//! The graph6, sparse6 and digraph6 formats of nauty, as written by geng and served by House of
+//! Graphs. Each graph is one line of printable ASCII, so a file of many graphs is read one line
+//! at a time through Graphs.
+
+use std::io::{BufRead, Lines, Write};
+use std::marker::PhantomData;
+use digraph::{self, DiGraph};
+use graph::{self, Graph};
+use {GraphRef, GraphMut, GraphError, LoopPolicy};
+use super::dense_size;
+
+/// Largest number of vertices N(n) fits in four bytes.
+const SHORT_MAX: usize = 258_047;
+
+/// Iterator over the graphs in a file of graph6 or sparse6 lines, or of digraph6 lines. Blank
+/// lines and format headers are skipped, and a malformed line yields a Parse error with its line
+/// number without ending the iteration.
+pub struct Graphs<R, T> {
+    lines: Lines<R>,
+    /// Number of lines read so far.
+    line: usize,
+    /// Loop policy of every graph read.
+    loops: LoopPolicy,
+    graph: PhantomData<T>
+}
+
+pub fn read_graphs<R: BufRead>(reader: R) -> Graphs<R, Graph> {
+    //! Iterate over the undirected graphs in reader, one graph6 or sparse6 line each. Loops
+    //! are dropped, as under the default loop policy.
+    read_graphs_with(reader, LoopPolicy::default())
+}
+
+pub fn read_graphs_with<R: BufRead>(reader: R, loops: LoopPolicy) -> Graphs<R, Graph> {
+    //! Iterate over the undirected graphs in reader, giving each the loop policy loops. A line
+    //! with a loop under Forbid yields a Parse error.
+    Graphs { lines: reader.lines(), line: 0, loops, graph: PhantomData }
+}
+
+pub fn read_digraphs<R: BufRead>(reader: R) -> Graphs<R, DiGraph> {
+    //! Iterate over the directed graphs in reader, one digraph6 line each. Loops are dropped.
+    read_digraphs_with(reader, LoopPolicy::default())
+}
+
+pub fn read_digraphs_with<R: BufRead>(reader: R, loops: LoopPolicy) -> Graphs<R, DiGraph> {
+    //! Iterate over the directed graphs in reader with the loop policy loops, as
+    //! read_graphs_with does.
+    Graphs { lines: reader.lines(), line: 0, loops, graph: PhantomData }
+}
+
+/// A graph type with a one line encoding.
+pub trait Decode: Sized {
+    /// Parse one line, without its header or line break, into a graph with the given policy.
+    fn decode(s: &str, loops: LoopPolicy) -> Result<Self, String>;
+}
+
+impl Decode for Graph {
+    fn decode(s: &str, loops: LoopPolicy) -> Result<Graph, String> {
+        if s.starts_with(':') { sparse6(s, loops) } else { graph6(s, loops) }
+    }
+}
+
+impl Decode for DiGraph {
+    fn decode(s: &str, loops: LoopPolicy) -> Result<DiGraph, String> {
+        digraph6(s, loops)
+    }
+}
+
+impl<R: BufRead, T: Decode> Iterator for Graphs<R, T> {
+    type Item = Result<T, GraphError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in self.lines.by_ref() {
+            self.line += 1;
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into()))
+            };
+            // a header has no line break of its own, so the first graph follows it directly
+            let s = [">>graph6<<", ">>sparse6<<", ">>digraph6<<"].iter()
+                .fold(line.trim(), |s, h| s.trim_start_matches(h));
+            if !s.is_empty() {
+                let line = self.line;
+                let graph = T::decode(s, self.loops);
+                return Some(graph.map_err(|msg| GraphError::Parse { line, msg }));
+            }
+        }
+        None
+    }
+}
+
+fn parse<T, F: Fn(&str) -> Result<T, String>>(s: &str, f: F) -> Result<T, GraphError> {
+    f(s.trim()).map_err(|msg| GraphError::Parse { line: 1, msg })
+}
+
+pub fn parse_graph6(s: &str) -> Result<Graph, GraphError> {
+    //! Parse a graph6 string, without header, into a graph.
+    parse(s, |s| graph6(s, LoopPolicy::default()))
+}
+
+pub fn parse_sparse6(s: &str) -> Result<Graph, GraphError> {
+    //! Parse a sparse6 string, leading colon included, into a graph. Loops are dropped, as
+    //! under the default loop policy, and repeated edges collapse into one.
+    parse_sparse6_with(s, LoopPolicy::default())
+}
+
+pub fn parse_sparse6_with(s: &str, loops: LoopPolicy) -> Result<Graph, GraphError> {
+    //! Parse a sparse6 string into a graph with the given loop policy, so the loops to_sparse6
+    //! writes for a graph with LoopPolicy::Allow are read back. A loop under Forbid is a Parse
+    //! error.
+    parse(s, |s| sparse6(s, loops))
+}
+
+pub fn parse_digraph6(s: &str) -> Result<DiGraph, GraphError> {
+    //! Parse a digraph6 string, leading ampersand included, into a directed graph. Loops are
+    //! dropped, as under the default loop policy.
+    parse_digraph6_with(s, LoopPolicy::default())
+}
+
+pub fn parse_digraph6_with(s: &str, loops: LoopPolicy) -> Result<DiGraph, GraphError> {
+    //! Parse a digraph6 string into a directed graph with the given loop policy, as
+    //! parse_sparse6_with does.
+    parse(s, |s| digraph6(s, loops))
+}
+
+pub fn to_graph6<G: GraphRef>(g: &G) -> String {
+    //! Encode g as graph6. Edges are read with has_edge(i, j) for i < j, so g should be
+    //! undirected; loops cannot be written and are left out.
+    let n = g.len();
+    let mut out = size(n);
+    let bits = (1..n).flat_map(|j| (0..j).map(move |i| (i, j))).map(|(i, j)| g.has_edge(i, j));
+    pack(bits, &mut out);
+    String::from_utf8(out).unwrap()
+}
+
+pub fn to_sparse6<G: GraphRef>(g: &G) -> String {
+    //! Encode g as sparse6, loops included. g should be undirected.
+    let n = g.len();
+    let k = width(n);
+    let mut bits = Vec::new();
+    let push = |b: bool, x: usize, bits: &mut Vec<bool>| {
+        bits.push(b);
+        bits.extend((0..k).rev().map(|i| (x >> i) & 1 == 1));
+    };
+    // edges {u, v} with u <= v, in order of v and then u
+    let mut cur = 0;
+    for v in 0..n {
+        for u in g.out_neighbor_iter(v).take_while(|&u| u <= v) {
+            if v == cur {
+                push(false, u, &mut bits);
+            } else if v == cur + 1 {
+                push(true, u, &mut bits);
+            } else {
+                push(true, v, &mut bits);
+                push(false, u, &mut bits);
+            }
+            cur = v;
+        }
+    }
+    let pad = (6 - bits.len() % 6) % 6;
+    // padding with ones would read as a loop at n - 1 when n - 1 fits in k bits exactly and
+    // the last edge ends at n - 2, so one 0 bit goes first to step past it
+    if k < 6 && n == 1 << k && pad > k && cur + 2 == n {
+        bits.push(false);
+        bits.extend((1..pad).map(|_| true));
+    } else {
+        bits.extend((0..pad).map(|_| true));
+    }
+    let mut out = vec![b':'];
+    out.extend(size(n));
+    pack(bits.into_iter(), &mut out);
+    String::from_utf8(out).unwrap()
+}
+
+pub fn to_digraph6<G: GraphRef>(g: &G) -> String {
+    //! Encode g as digraph6, loops included.
+    let n = g.len();
+    let mut out = vec![b'&'];
+    out.extend(size(n));
+    pack((0..n * n).map(|x| g.has_edge(x / n, x % n)), &mut out);
+    String::from_utf8(out).unwrap()
+}
+
+pub fn write_graph6<G: GraphRef, W: Write>(g: &G, writer: &mut W) -> Result<(), GraphError> {
+    //! Write g as one graph6 line, so calls for many graphs make a file read_graphs reads back.
+    writeln!(writer, "{}", to_graph6(g))?;
+    Ok(())
+}
+
+pub fn write_sparse6<G: GraphRef, W: Write>(g: &G, writer: &mut W) -> Result<(), GraphError> {
+    //! Write g as one sparse6 line.
+    writeln!(writer, "{}", to_sparse6(g))?;
+    Ok(())
+}
+
+pub fn write_digraph6<G: GraphRef, W: Write>(g: &G, writer: &mut W) -> Result<(), GraphError> {
+    //! Write g as one digraph6 line, so calls for many graphs make a file read_digraphs reads
+    //! back.
+    writeln!(writer, "{}", to_digraph6(g))?;
+    Ok(())
+}
+
+fn size(n: usize) -> Vec<u8> {
+    // N(n): one byte up to 62, then 126 and three bytes, then 126 126 and six bytes
+    let digits = |count: usize| (0..count).rev().map(move |i| ((n >> (6 * i)) & 63) as u8 + 63);
+    match n {
+        0..=62 => vec![n as u8 + 63],
+        63..=SHORT_MAX => Some(126).into_iter().chain(digits(3)).collect(),
+        _ => vec![126, 126].into_iter().chain(digits(6)).collect()
+    }
+}
+
+fn pack<I: Iterator<Item = bool>>(bits: I, out: &mut Vec<u8>) {
+    // six bits to a byte, most significant first, the last byte padded with zeros
+    let (mut byte, mut count) = (0, 0);
+    for b in bits {
+        byte = byte << 1 | b as u8;
+        count += 1;
+        if count == 6 {
+            out.push(byte + 63);
+            byte = 0;
+            count = 0;
+        }
+    }
+    if count > 0 {
+        out.push((byte << (6 - count)) + 63);
+    }
+}
+
+fn width(n: usize) -> usize {
+    // bits needed to write n - 1
+    (0..).find(|&k| n.saturating_sub(1) >> k == 0).unwrap()
+}
+
+fn bytes(s: &str) -> Result<Vec<u8>, String> {
+    // the six bits each byte holds
+    s.bytes().enumerate().map(|(i, c)| match c {
+        63..=126 => Ok(c - 63),
+        _ => Err(format!("byte {} is {:?}, outside '?' to '~'", i, c as char))
+    }).collect()
+}
+
+fn read_size(data: &[u8]) -> Result<(usize, &[u8]), String> {
+    let (count, skip) = match data {
+        [63, 63, ..] => (6, 2),
+        [63, ..] => (3, 1),
+        [_, ..] => (1, 0),
+        [] => return Err("missing number of vertices".to_string())
+    };
+    if data.len() < skip + count {
+        return Err("truncated number of vertices".to_string());
+    }
+    let n = data[skip..skip + count].iter().fold(0, |n, &d| n << 6 | d as usize);
+    Ok((n, &data[skip + count..]))
+}
+
+fn bit(data: &[u8], i: usize) -> bool {
+    (data[i / 6] >> (5 - i % 6)) & 1 == 1
+}
+
+fn expect_bits(data: &[u8], nbits: usize) -> Result<(), String> {
+    let want = nbits.div_ceil(6);
+    if data.len() == want {
+        Ok(())
+    } else {
+        Err(format!("{} bytes of edges, expected {}", data.len(), want))
+    }
+}
+
+fn too_big(n: usize) -> String {
+    format!("{} vertices, too many to address their pairs", n)
+}
+
+fn graph6(s: &str, loops: LoopPolicy) -> Result<Graph, String> {
+    let data = bytes(s)?;
+    let (n, data) = read_size(&data)?;
+    // the edge bits must be present, which bounds n before anything is allocated
+    let nbits = n.checked_mul(n.saturating_sub(1)).ok_or_else(|| too_big(n))? / 2;
+    expect_bits(data, nbits)?;
+    let mut g = graph::with_loops(n, loops);
+    let pairs = (1..n).flat_map(|j| (0..j).map(move |i| (i, j)));
+    for (x, (i, j)) in pairs.enumerate() {
+        if bit(data, x) {
+            g.add_edge(i, j);
+        }
+    }
+    Ok(g)
+}
+
+fn sparse6(s: &str, loops: LoopPolicy) -> Result<Graph, String> {
+    if !s.starts_with(':') {
+        return Err("sparse6 must start with ':'".to_string());
+    }
+    let data = bytes(&s[1..])?;
+    let (n, data) = read_size(&data)?;
+    // edges are listed, so only a limit keeps a short line from asking for a huge matrix
+    let mut g = graph::with_loops(dense_size(n)?, loops);
+    let k = width(n);
+    let nbits = data.len() * 6;
+    let (mut pos, mut v) = (0, 0);
+    // each group is a bit b and a k bit x; a group cut short by the end is padding
+    while pos + 1 + k <= nbits {
+        let b = bit(data, pos);
+        let x = (pos + 1..pos + 1 + k).fold(0, |x, i| x << 1 | bit(data, i) as usize);
+        pos += 1 + k;
+        if b {
+            v += 1;
+        }
+        if v >= n {
+            break;
+        }
+        if x > v {
+            v = x;
+        } else {
+            g.try_add_edge(x, v).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(g)
+}
+
+fn digraph6(s: &str, loops: LoopPolicy) -> Result<DiGraph, String> {
+    if !s.starts_with('&') {
+        return Err("digraph6 must start with '&'".to_string());
+    }
+    let data = bytes(&s[1..])?;
+    let (n, data) = read_size(&data)?;
+    expect_bits(data, n.checked_mul(n).ok_or_else(|| too_big(n))?)?;
+    let mut g = digraph::with_loops(n, loops);
+    for x in (0..n * n).filter(|&x| bit(data, x)) {
+        g.try_add_edge(x / n, x % n).map_err(|e| e.to_string())?;
+    }
+    Ok(g)
+}
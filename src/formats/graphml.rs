@@ -0,0 +1,373 @@
+//! GraphML, the XML format Gephi and yEd exchange. Node and edge data declared by <key>
+//! elements map to the attribute maps serialize_dot takes, by attr.name. Only the parts of
+//! XML GraphML files use are read: elements, attributes, text, entities, CDATA, comments and
+//! declarations.
+
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, Read, Write};
+use digraph::{self, DiGraph};
+use graph::{self, Graph};
+use {GraphRef, GraphMut, GraphError, LoopPolicy, NodeAttrs, EdgeAttrs};
+use super::dense_size;
+
+/// A graph read from GraphML, with its node ids and attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphMl<G> {
+    pub graph: G,
+    /// ids[v] is the GraphML id of v; vertices are numbered in the order nodes appear.
+    pub ids: Vec<String>,
+    /// Data of each node that has any, key defaults included.
+    pub node_attrs: NodeAttrs,
+    /// Data of each edge that has any, keyed by (min, max) in an undirected graph.
+    pub edge_attrs: EdgeAttrs
+}
+
+impl<G> GraphMl<G> {
+    pub fn index_map(&self) -> Option<Vec<usize>> {
+        //! Return k for each node id nk, as write_graphml writes them, or None if some id has
+        //! another form. For a graph written with the map compressed returned, this is that
+        //! map again.
+        self.ids.iter().map(|id| {
+            let k = id.strip_prefix('n')?;
+            k.parse::<usize>().ok().filter(|v| v.to_string() == k)
+        }).collect()
+    }
+}
+
+pub fn read_graphml<R: Read>(reader: &mut io::BufReader<R>) -> Result<GraphMl<Graph>, GraphError> {
+    //! Read GraphML into an undirected graph, whatever the edgedefault of the file. Loops are
+    //! dropped, with their data. A file with more than formats::DENSE_LIMIT nodes is a Parse
+    //! error.
+    read_graphml_with(reader, LoopPolicy::default())
+}
+
+pub fn read_graphml_with<R: Read>(reader: &mut io::BufReader<R>, loops: LoopPolicy)
+    -> Result<GraphMl<Graph>, GraphError> {
+    //! Read GraphML into an undirected graph with the given loop policy, so the loops
+    //! write_graphml writes for a graph with LoopPolicy::Allow are read back with their data.
+    //! A loop under Forbid is an error.
+    let mut s = String::new();
+    reader.read_to_string(&mut s)?;
+    parse(&s, false, |n| graph::with_loops(n, loops))
+}
+
+pub fn read_graphml_directed<R: Read>(reader: &mut io::BufReader<R>)
+    -> Result<GraphMl<DiGraph>, GraphError> {
+    //! Read GraphML into a directed graph, taking each edge from its source to its target.
+    read_graphml_directed_with(reader, LoopPolicy::default())
+}
+
+pub fn read_graphml_directed_with<R: Read>(reader: &mut io::BufReader<R>, loops: LoopPolicy)
+    -> Result<GraphMl<DiGraph>, GraphError> {
+    //! Read GraphML into a directed graph with the given loop policy, as read_graphml_with does.
+    let mut s = String::new();
+    reader.read_to_string(&mut s)?;
+    parse(&s, true, |n| digraph::with_loops(n, loops))
+}
+
+pub fn write_graphml<G: GraphRef, W: Write>(g: &G, ids: Option<&[usize]>,
+                                            node_attrs: Option<&NodeAttrs>,
+                                            edge_attrs: Option<&EdgeAttrs>, writer: &mut W)
+    -> Result<(), GraphError> {
+    //! Write g as GraphML, directed if g is, declaring a string key for each attribute name in
+    //! node_attrs and edge_attrs. Vertex v gets the id n{ids[v]} if ids is given, such as the
+    //! map compressed returns, and n{v} otherwise.
+    let key_names = |maps: Vec<&HashMap<String, String>>| -> Vec<String> {
+        let names: BTreeSet<&String> = maps.into_iter().flat_map(|a| a.keys()).collect();
+        names.into_iter().cloned().collect()
+    };
+    let node_keys = key_names(node_attrs.into_iter().flat_map(|m| m.values()).collect());
+    let edge_keys = key_names(edge_attrs.into_iter().flat_map(|m| m.values()).collect());
+    writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(writer, "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">")?;
+    for (i, k) in node_keys.iter().enumerate() {
+        writeln!(writer, "  <key id=\"n_{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>",
+                 i, escape(k))?;
+    }
+    for (i, k) in edge_keys.iter().enumerate() {
+        writeln!(writer, "  <key id=\"e_{}\" for=\"edge\" attr.name=\"{}\" attr.type=\"string\"/>",
+                 i, escape(k))?;
+    }
+    let default = if g.is_directed() { "directed" } else { "undirected" };
+    writeln!(writer, "  <graph edgedefault=\"{}\">", default)?;
+    let id = |v: usize| format!("n{}", ids.map_or(v, |ids| ids[v]));
+    let data = |writer: &mut W, prefix: &str, keys: &[String], attrs: Option<&HashMap<_, _>>|
+        -> io::Result<()> {
+        for (i, k) in keys.iter().enumerate() {
+            if let Some(v) = attrs.and_then(|a: &HashMap<String, String>| a.get(k)) {
+                writeln!(writer, "      <data key=\"{}_{}\">{}</data>", prefix, i, escape(v))?;
+            }
+        }
+        Ok(())
+    };
+    for v in 0..g.len() {
+        let attrs = node_attrs.and_then(|m| m.get(&v));
+        if attrs.is_some_and(|a| !a.is_empty()) {
+            writeln!(writer, "    <node id=\"{}\">", id(v))?;
+            data(writer, "n", &node_keys, attrs)?;
+            writeln!(writer, "    </node>")?;
+        } else {
+            writeln!(writer, "    <node id=\"{}\"/>", id(v))?;
+        }
+    }
+    for (u, v) in g.edges() {
+        let attrs = edge_attrs.and_then(|m| m.get(&(u, v)));
+        let edge = format!("<edge source=\"{}\" target=\"{}\"", id(u), id(v));
+        if attrs.is_some_and(|a| !a.is_empty()) {
+            writeln!(writer, "    {}>", edge)?;
+            data(writer, "e", &edge_keys, attrs)?;
+            writeln!(writer, "    </edge>")?;
+        } else {
+            writeln!(writer, "    {}/>", edge)?;
+        }
+    }
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// A piece of XML, with the line it starts on.
+#[derive(Debug)]
+enum Event {
+    /// An element's local name, attributes by local name, and whether it closes itself.
+    Start(String, HashMap<String, String>, bool),
+    End(String),
+    Text(String)
+}
+
+fn local(name: &str) -> String {
+    // drop the namespace prefix
+    name.rsplit(':').next().unwrap().to_string()
+}
+
+fn unescape(s: &str, line: usize) -> Result<String, GraphError> {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        let end = rest[i..].find(';').ok_or_else(|| GraphError::Parse {
+            line, msg: "unterminated entity".to_string()
+        })?;
+        let entity = &rest[i + 1..i + end];
+        let c = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16).ok()
+                .and_then(std::char::from_u32),
+            _ if entity.starts_with('#') => {
+                entity[1..].parse().ok().and_then(std::char::from_u32)
+            }
+            _ => None
+        };
+        match c {
+            Some(c) => out.push(c),
+            None => {
+                let msg = format!("unknown entity &{};", entity);
+                return Err(GraphError::Parse { line, msg });
+            }
+        }
+        rest = &rest[i + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn events(s: &str) -> Result<Vec<(Event, usize)>, GraphError> {
+    let mut events = Vec::new();
+    let (mut pos, mut line, mut counted) = (0, 1, 0);
+    while pos < s.len() {
+        line += s[counted..pos].matches('\n').count();
+        counted = pos;
+        let err = |msg: String| GraphError::Parse { line, msg };
+        let rest = &s[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            events.push((Event::Text(unescape(&rest[..end], line)?), line));
+            pos += end;
+            continue;
+        }
+        // markup that is not an element: its terminator, and whether it holds text
+        let skip = [("<?", "?>", false), ("<!--", "-->", false), ("<![CDATA[", "]]>", true),
+                    ("<!", ">", false)];
+        if let Some(&(open, close, text)) = skip.iter().find(|m| rest.starts_with(m.0)) {
+            let end = rest.find(close).ok_or_else(|| err(format!("unterminated {}", open)))?;
+            if text {
+                events.push((Event::Text(rest[open.len()..end].to_string()), line));
+            }
+            pos += end + close.len();
+            continue;
+        }
+        // find the end of the tag, skipping > inside quoted attribute values
+        let mut quote = None;
+        let end = rest.char_indices().skip(1).find(|&(_, c)| {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None => return c == '>'
+            }
+            false
+        }).map(|(i, _)| i).ok_or_else(|| err("unterminated tag".to_string()))?;
+        let tag = &rest[1..end];
+        if let Some(name) = tag.strip_prefix('/') {
+            events.push((Event::End(local(name.trim())), line));
+        } else {
+            let closed = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let mut attrs = HashMap::new();
+            let mut body = tag[name_end..].trim_start();
+            while !body.is_empty() {
+                let eq = body.find('=')
+                    .ok_or_else(|| err("attribute without value".to_string()))?;
+                let name = local(body[..eq].trim());
+                let value = body[eq + 1..].trim_start();
+                let q = value.chars().next().filter(|&c| c == '"' || c == '\'')
+                    .ok_or_else(|| err("unquoted attribute value".to_string()))?;
+                let close = value[1..].find(q)
+                    .ok_or_else(|| err("unterminated attribute value".to_string()))?;
+                attrs.insert(name, unescape(&value[1..close + 1], line)?);
+                body = value[close + 2..].trim_start();
+            }
+            events.push((Event::Start(local(&tag[..name_end]), attrs, closed), line));
+        }
+        pos += end + 1;
+    }
+    Ok(events)
+}
+
+/// What data elements currently describe.
+enum Owner {
+    Node(usize),
+    Edge(usize),
+    Other
+}
+
+fn parse<G, F>(s: &str, directed: bool, make: F) -> Result<GraphMl<G>, GraphError>
+    where G: GraphMut, F: FnOnce(usize) -> G
+{
+    // key id to (domain, attribute name, default)
+    let mut keys: HashMap<String, (String, String, Option<String>)> = HashMap::new();
+    let mut ids = Vec::new();
+    let mut index = HashMap::new();
+    let mut nodes: Vec<HashMap<String, String>> = Vec::new();
+    let mut edges: Vec<(String, String, HashMap<String, String>, usize)> = Vec::new();
+    let mut owner = Owner::Other;
+    // the key of the open data or default element, and its text so far
+    let mut data: Option<(Option<String>, String)> = None;
+    let mut key = None;
+    for (event, line) in events(s)? {
+        let err = |msg: String| GraphError::Parse { line, msg };
+        let attr = |attrs: &HashMap<String, String>, name: &str| attrs.get(name).cloned()
+            .ok_or_else(|| err(format!("missing attribute {}", name)));
+        match event {
+            Event::Start(name, attrs, closed) => match name.as_str() {
+                "key" => {
+                    let id = attr(&attrs, "id")?;
+                    let domain = attrs.get("for").cloned().unwrap_or_else(|| "all".to_string());
+                    let name = attrs.get("attr.name").cloned().unwrap_or_else(|| id.clone());
+                    keys.insert(id.clone(), (domain, name, None));
+                    if !closed {
+                        key = Some(id);
+                    }
+                }
+                "default" if key.is_some() => data = Some((None, String::new())),
+                "node" => {
+                    let id = attr(&attrs, "id")?;
+                    if index.insert(id.clone(), ids.len()).is_some() {
+                        return Err(err(format!("node id {:?} is used twice", id)));
+                    }
+                    // the matrix is sized by the node count, so it is bounded as nodes appear
+                    dense_size(ids.len() + 1).map_err(&err)?;
+                    owner = if closed { Owner::Other } else { Owner::Node(ids.len()) };
+                    ids.push(id);
+                    nodes.push(HashMap::new());
+                }
+                "edge" => {
+                    let (u, v) = (attr(&attrs, "source")?, attr(&attrs, "target")?);
+                    owner = if closed { Owner::Other } else { Owner::Edge(edges.len()) };
+                    edges.push((u, v, HashMap::new(), line));
+                }
+                "data" if !closed => data = Some((Some(attr(&attrs, "key")?), String::new())),
+                _ => {}
+            },
+            Event::Text(t) => {
+                if let Some((_, ref mut text)) = data {
+                    text.push_str(&t);
+                }
+            }
+            Event::End(name) => match name.as_str() {
+                "key" => key = None,
+                "node" | "edge" => owner = Owner::Other,
+                "default" | "data" => {
+                    let (k, text) = match data.take() {
+                        Some(d) => d,
+                        None => continue
+                    };
+                    let value = text.trim().to_string();
+                    let k = match k {
+                        Some(k) => k,
+                        None => {
+                            // the default of the open key
+                            if let Some(entry) = key.as_ref().and_then(|k| keys.get_mut(k)) {
+                                entry.2 = Some(value);
+                            }
+                            continue;
+                        }
+                    };
+                    let name = match keys.get(&k) {
+                        Some((_, name, _)) => name.clone(),
+                        None => return Err(err(format!("undeclared key {:?}", k)))
+                    };
+                    match owner {
+                        Owner::Node(v) => { nodes[v].insert(name, value); }
+                        Owner::Edge(e) => { edges[e].2.insert(name, value); }
+                        Owner::Other => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    // key defaults fill in what nodes and edges leave out
+    let defaults = |domain: &str| -> Vec<(String, String)> {
+        keys.values().filter(|(d, _, _)| d == domain || d == "all")
+            .filter_map(|(_, name, default)| default.clone().map(|v| (name.clone(), v)))
+            .collect()
+    };
+    let (node_defaults, edge_defaults) = (defaults("node"), defaults("edge"));
+    let mut graph = make(ids.len());
+    let mut node_attrs = NodeAttrs::new();
+    for (v, mut attrs) in nodes.into_iter().enumerate() {
+        for (k, d) in node_defaults.iter() {
+            attrs.entry(k.clone()).or_insert_with(|| d.clone());
+        }
+        if !attrs.is_empty() {
+            node_attrs.insert(v, attrs);
+        }
+    }
+    let mut edge_attrs = EdgeAttrs::new();
+    for (source, target, mut attrs, line) in edges {
+        let vertex = |id: &String| index.get(id).cloned().ok_or_else(|| GraphError::Parse {
+            line, msg: format!("edge to unknown node {:?}", id)
+        });
+        let (u, v) = (vertex(&source)?, vertex(&target)?);
+        graph.try_add_edge(u, v)?;
+        for (k, d) in edge_defaults.iter() {
+            attrs.entry(k.clone()).or_insert_with(|| d.clone());
+        }
+        // a loop the graph drops takes its data with it
+        if !attrs.is_empty() && graph.has_edge(u, v) {
+            let e = if directed || u <= v { (u, v) } else { (v, u) };
+            edge_attrs.entry(e).or_default().extend(attrs);
+        }
+    }
+    Ok(GraphMl { graph, ids, node_attrs, edge_attrs })
+}
@@ -0,0 +1,21 @@
+//! Readers and writers for the text formats graphs are exchanged in, besides the 0/1 csv
+//! matrices of graph::read_csv and digraph::read_csv.
+
+pub mod dimacs;
+pub mod dot;
+pub mod edgelist;
+pub mod graph6;
+pub mod graphml;
+
+/// Largest number of vertices a reader builds a dense Graph or DiGraph for. Their matrices take
+/// n^2 bits, 512 MiB at this size, so a header asking for more fails with a Parse error instead
+/// of aborting on the allocation.
+pub const DENSE_LIMIT: usize = 1 << 16;
+
+fn dense_size(n: usize) -> Result<usize, String> {
+    if n <= DENSE_LIMIT {
+        Ok(n)
+    } else {
+        Err(format!("{} vertices, more than the {} of a dense graph", n, DENSE_LIMIT))
+    }
+}
@@ -1,28 +1,51 @@
 extern crate rand;
 
 use std::io;
-use std::io::{BufRead, Read};
-use std::collections::{BitVec, HashMap, BitSet};
+use std::io::{BufRead, Read, Write};
+use std::borrow::Cow;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Index;
+use bitset::BitVec;
+use bitset::kernels;
 use rand::random;
-use BitGraph;
+use matrix;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, NodeAttrs, EdgeAttrs};
 
-pub type Graph = Vec<BitVec>;
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Graph {
+    /// rows[i] is the neighborhood of i.
+    rows: Vec<BitVec>,
+    /// Whether rows[i][i] may be set.
+    loops: LoopPolicy
+}
 
 pub fn new(size: usize) -> Graph {
     //! Construct new Graph with size number of vertices.
-    vec![BitVec::from_elem(size, false); size]
+    with_loops(size, LoopPolicy::default())
+}
+
+pub fn with_loops(size: usize, loops: LoopPolicy) -> Graph {
+    //! Construct new Graph with size number of vertices and the given loop policy.
+    Graph {
+        rows: matrix::new(size),
+        loops
+    }
 }
 
 pub fn complete(n: usize) -> Graph {
     //! Construct K_n.
-    (0..n).map(|i| BitVec::from_fn(n, |j| i != j)).collect()
+    Graph {
+        rows: (0..n).map(|i| BitVec::from_fn(n, |j| i != j)).collect(),
+        loops: LoopPolicy::default()
+    }
 }
 
 pub fn erdos_renyi(n: usize, p: f64) -> Graph {
     //! Construct Erdos-Renyi random undirected graph on n vertices, p in range [0,1].
     let mut g = new(n);
-    for i in (0..n) {
-        for j in (i+1..n) {
+    for i in 0..n {
+        for j in i+1..n {
             if random::<f64>() < p {
                 g.add_edge(i,j);
             }
@@ -32,115 +55,308 @@ pub fn erdos_renyi(n: usize, p: f64) -> Graph {
     g
 }
 
-impl BitGraph for Graph {
-    fn verify(&self) -> bool {
-        //! Make sure matrix is symmetric.
-        let n = self.len();
-        n == 0 || (self.iter().all(|v| v.len() == n) &&
-                (0..n).all(|i| (0..n).all(|j| self[i][j] == self[j][i])))
+impl Graph {
+    pub fn set_loop_policy(&mut self, loops: LoopPolicy) -> Result<(), GraphError> {
+        //! Change how self treats loops. Switching to Ignore drops existing loops, and switching
+        //! to Forbid fails if there are any.
+        match loops {
+            LoopPolicy::Allow => {}
+            LoopPolicy::Ignore => matrix::clear_diagonal(&mut self.rows),
+            LoopPolicy::Forbid => if let Some(v) = matrix::has_diagonal(&self.rows) {
+                return Err(GraphError::SelfLoop(v));
+            }
+        }
+        self.loops = loops;
+        Ok(())
     }
+
+    fn link(&mut self, fr: usize, to: usize) {
+        // add an edge created by an operation rather than asked for, dropping disallowed loops
+        if fr != to || self.loops == LoopPolicy::Allow {
+            self.rows[fr].set(to, true);
+            self.rows[to].set(fr, true);
+        }
+    }
+}
+
+impl GraphRef for Graph {
     fn len(&self) -> usize {
-        self.len()
+        self.rows.len()
     }
-    fn add_edge(&mut self, fr: usize, to: usize) {
-        self[fr].set(to, true);
-        self[to].set(fr, true);
+    fn is_directed(&self) -> bool {
+        false
     }
-    fn remove_edge(&mut self, fr: usize, to: usize) {
-        self[fr].set(to, false);
-        self[to].set(fr, false);
+    fn loop_policy(&self) -> LoopPolicy {
+        self.loops
     }
     fn neighbors(&self, id: usize) -> BitVec {
-        self[id].clone()
+        self.rows[id].clone()
     }
-    fn in_neighbors(&self, id: usize) -> &BitVec {
-        &self[id]
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.rows[id])
     }
-    fn out_neighbors(&self, id: usize) -> &BitVec {
-        &self[id]
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.rows[id])
     }
-    fn complement(&self) -> Graph {
-        // negate each row and zero the diagonal
-        let mut new_graph = self.clone();
-        for (i,r) in new_graph.iter_mut().enumerate() {
-            r.negate();
-            r.set(i, false);
+}
+
+impl GraphMut for Graph {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        //! Panics on a loop if loops are forbidden.
+        if let Err(e) = self.try_add_edge(fr, to) {
+            panic!("{}", e);
         }
-        new_graph
     }
-    fn induce(&mut self, vertices: &BitVec) {
-        for (i, r) in self.iter_mut().enumerate() {
-            if vertices[i] {
-                r.intersect(&vertices);
-            } else {
-                r.set_all();
-                r.negate();
-            }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.check_vertex(fr)?;
+        self.check_vertex(to)?;
+        if fr != to || self.loops.admit(fr)? {
+            self.rows[fr].set(to, true);
+            self.rows[to].set(fr, true);
         }
+        Ok(())
     }
-    fn compressed(&self) -> (Graph, Vec<usize>) {
-        // construct mapping of new indices -> old indices
-        let map = (0..self.len()).filter(|&idx| self[idx].any()).collect();
-        let mut new_graph = self.clone();
-        // retain all connected vertices
-        new_graph.retain(BitVec::any);
-        (new_graph, map)
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.rows[fr].set(to, false);
+        self.rows[to].set(fr, false);
+    }
+    fn add_vertex(&mut self) -> usize {
+        matrix::add_vertex(&mut self.rows)
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        matrix::remove_vertex(&mut self.rows, v, mode)
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        matrix::induce(&mut self.rows, vertices);
     }
     fn contract(&mut self, e: (usize, usize)) {
         let (fr, to) = e;
-        assert!(self[fr][to]);
-        for (i,r) in self.iter_mut().enumerate() {
-            if i == to {
-                r.set_all();
-                r.negate();
-            } else if r[to] {
-                r.set(fr, true);
-                r.set(to, false);
-            }
+        assert!(self.rows[fr][to]);
+        // detach to, then give each of its neighbors to fr; the edge itself and a loop at to
+        // become a loop at fr if loops are allowed
+        let to_row = self.rows[to].clone();
+        for w in kernels::ones(&to_row) {
+            self.remove_edge(to, w);
+            self.link(fr, if w == to { fr } else { w });
         }
     }
+}
+
+impl BitGraph for Graph {
+    fn verify(&self) -> bool {
+        //! Make sure matrix is symmetric, with no loops unless they are allowed.
+        let n = self.len();
+        matrix::is_square(&self.rows) &&
+            (0..n).all(|i| (0..n).all(|j| self.rows[i][j] == self.rows[j][i])) &&
+            (self.loops == LoopPolicy::Allow || matrix::has_diagonal(&self.rows).is_none())
+    }
+    fn complement(&self) -> Graph {
+        // negate each row; the diagonal is negated too only if loops are edges
+        Graph {
+            rows: matrix::complement(&self.rows, self.loops == LoopPolicy::Allow),
+            loops: self.loops
+        }
+    }
+    fn compressed(&self) -> (Graph, Vec<usize>) {
+        // construct mapping of new indices -> old indices
+        let map: Vec<usize> = (0..self.len()).filter(|&idx| self.rows[idx].any()).collect();
+        // retain all connected vertices
+        (Graph {
+            rows: matrix::select(&self.rows, &map),
+            loops: self.loops
+        }, map)
+    }
     fn reordered(&self, order: &[usize]) -> Self {
-        (0..self.len()).map(|v| BitVec::from_fn(self.len(), |w|
-                                                self[order[v]][order[w]])).collect()
+        Graph {
+            rows: matrix::select(&self.rows, order),
+            loops: self.loops
+        }
     }
-    fn serialize_dot(&self, node_attrs: Option<&HashMap<usize, HashMap<String, String>>>,
-                            edge_attrs: Option<&HashMap<(usize, usize), HashMap<String, String>>>)
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
         -> String
     {
-        let mut out_lines = vec![format!("node [fontname=\"{}\",fontsize=\"{}\"]",
-                                            "sans-serif", "12")];
-        for (i,_) in self.iter().enumerate() {
-            let mut n_props = vec![format!("id={}", i)];
-            if let Some(attrs) = node_attrs {
-                n_props.push_all(&attrs[&i].iter().map(|(k,v)| format!("{}=\"{}\"", k,
-                                                                        v)).collect::<Vec<_>>());
+        serialize_undirected_dot(self, node_attrs, edge_attrs)
+    }
+}
+
+impl Index<(usize, usize)> for Graph {
+    type Output = bool;
+
+    fn index(&self, e: (usize, usize)) -> &bool {
+        //! Whether e is an edge; g[(u, v)] is g.has_edge(u, v).
+        &self.rows[e.0][e.1]
+    }
+}
+
+impl Extend<(usize, usize)> for Graph {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, edges: I) {
+        //! Add each edge, first adding vertices up to the larger end if it is out of bounds.
+        for (u, v) in edges {
+            while self.len() <= u.max(v) {
+                self.add_vertex();
+            }
+            self.add_edge(u, v);
+        }
+    }
+}
+
+impl FromIterator<(usize, usize)> for Graph {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(edges: I) -> Graph {
+        //! Collect edges into a graph on 0..=max id with the default loop policy.
+        let mut g = new(0);
+        g.extend(edges);
+        g
+    }
+}
+
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //! Write one line per vertex: its id, a colon, and its neighbors.
+        fmt_adjacency(self, f)
+    }
+}
+
+pub(crate) fn fmt_adjacency<G: GraphRef>(g: &G, f: &mut fmt::Formatter) -> fmt::Result {
+    //! Write "v: w1 w2 ..." for each vertex v with out neighbors w1, w2, ....
+    for v in 0..g.len() {
+        write!(f, "{}:", v)?;
+        for w in g.out_neighbor_iter(v) {
+            write!(f, " {}", w)?;
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+pub fn serialize_undirected_dot<G: GraphRef>(g: &G, node_attrs: Option<&NodeAttrs>,
+                                             edge_attrs: Option<&EdgeAttrs>) -> String {
+    //! Serialize any undirected graph to DOT GraphViz format, writing each edge once.
+    write_dot(g, false, None, dot_preamble(), node_attrs, edge_attrs)
+}
+
+pub(crate) fn dot_preamble() -> Vec<String> {
+    //! Default statements written before the nodes of every DOT graph.
+    vec![format!("node [fontname=\"{}\",fontsize=\"{}\"]", "sans-serif", "12")]
+}
+
+pub(crate) fn dot_quote(s: &str) -> String {
+    //! Quote s as a DOT string, escaping the backslashes and quotes in it.
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn dot_attr(key: &str, value: &str) -> String {
+    //! Write key=value for a DOT attribute list. The value is always quoted, and the key only
+    //! when it is not a plain identifier or is a keyword.
+    let keywords = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+    let plain = key.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') &&
+        key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') &&
+        !keywords.contains(&key.to_ascii_lowercase().as_str());
+    let key = if plain { key.to_string() } else { dot_quote(key) };
+    format!("{}={}", key, dot_quote(value))
+}
+
+pub(crate) fn write_dot<G: GraphRef>(g: &G, directed: bool, names: Option<&[String]>,
+                                     preamble: Vec<String>, node_attrs: Option<&NodeAttrs>,
+                                     edge_attrs: Option<&EdgeAttrs>) -> String {
+    //! Serialize g to DOT, writing each undirected edge once or every arc if directed. The
+    //! preamble lines come first, and nodes are named by names if given and by their ids
+    //! otherwise.
+    let name = |i: usize| match names {
+        Some(n) => dot_quote(&n[i]),
+        None => i.to_string()
+    };
+    let mut out_lines = preamble;
+    for i in 0..g.len() {
+        let mut n_props = vec![format!("id={}", i)];
+        if let Some(attrs) = node_attrs {
+            if let Some(a) = attrs.get(&i) {
+                n_props.extend(a.iter().map(|(k,v)| dot_attr(k, v)));
             }
-            out_lines.push(format!("{} [{}]", i, n_props.connect(",")));
-            for j in BitSet::from_bit_vec(self.out_neighbors(i).clone()).iter().filter(|&j| i<=j) {
-                let mut e_props = vec![format!("id=\"{},{}\"", i,j)];
-                if let Some(attrs) = edge_attrs {
-                    e_props.push_all(&attrs[&(i,j)].iter().map(|(k,v)| format!("{}=\"{}\"", k,
-                                                                                v)).collect::<Vec<_>>());
+        }
+        out_lines.push(format!("{} [{}]", name(i), n_props.join(",")));
+        for j in g.out_neighbor_iter(i).filter(|&j| directed || i<=j) {
+            let mut e_props = vec![format!("id=\"{},{}\"", i,j)];
+            if let Some(attrs) = edge_attrs {
+                if let Some(a) = attrs.get(&(i,j)) {
+                    e_props.extend(a.iter().map(|(k,v)| dot_attr(k, v)));
                 }
-                out_lines.push(format!("{} -- {} [{}]", i, j, e_props.connect(",")));
             }
+            let op = if directed { "->" } else { "--" };
+            out_lines.push(format!("{} {} {} [{}]", name(i), op, name(j), e_props.join(",")));
         }
-        format!("strict graph {{\n{}\n}}", out_lines.connect("\n"))
     }
+    if directed {
+        return format!("digraph {{\n{}\n}}", out_lines.join("\n"));
+    }
+    // strict graphs may not keep loops, so only mark the output strict when it has none
+    let strict = if (0..g.len()).any(|i| g.has_edge(i, i)) { "" } else { "strict " };
+    format!("{}graph {{\n{}\n}}", strict, out_lines.join("\n"))
+}
+
+pub fn write_csv<G: GraphRef, W: Write>(g: &G, writer: &mut W) -> Result<(), GraphError> {
+    //! Write the adjacency matrix of g as csv of 0's and 1's, row i holding the in neighbors
+    //! of i, which is the layout graph::read_csv and digraph::read_csv expect.
+    for i in 0..g.len() {
+        let row: Vec<&str> = g.in_neighbors(i).iter().map(|b| if b { "1" } else { "0" })
+            .collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    Ok(())
+}
+
+pub(crate) fn read_matrix<R: Read>(reader: &mut io::BufReader<R>)
+    -> Result<Vec<BitVec>, GraphError> {
+    //! Read csv of 0's and 1's into square matrix rows, skipping blank lines.
+    let mut rows = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // parse each number into int, where only 1 is an edge
+        let row = line.split(',').map(|s| s.trim().parse::<i64>().map(|v| v == 1).map_err(
+            |e| GraphError::Parse { line: i + 1, msg: format!("{:?}: {}", s.trim(), e) }))
+            .collect::<Result<BitVec, _>>()?;
+        rows.push(row);
+    }
+    let n = rows.len();
+    match rows.iter().position(|r| r.len() != n) {
+        Some(i) => Err(GraphError::RaggedRow { row: i, len: rows[i].len(), expected: n }),
+        None => Ok(rows)
+    }
+}
+
+pub(crate) fn apply_loops(rows: &mut [BitVec], loops: LoopPolicy) -> Result<(), GraphError> {
+    //! Check or drop the diagonal of a matrix that was read in, according to loops.
+    match (loops, matrix::has_diagonal(rows)) {
+        (LoopPolicy::Forbid, Some(v)) => Err(GraphError::SelfLoop(v)),
+        (LoopPolicy::Ignore, Some(_)) => {
+            matrix::clear_diagonal(rows);
+            Ok(())
+        }
+        _ => Ok(())
+    }
+}
+
+pub fn read_csv<R: Read>(reader: &mut io::BufReader<R>) -> Result<Graph, GraphError> {
+    //! Read csv of 0's and 1's into a graph from given buffered reader, with the default loop
+    //! policy. Fail on malformed entries, ragged rows or an asymmetric matrix.
+    read_csv_with(reader, LoopPolicy::default())
 }
 
-pub fn read_csv<R: Read>(reader: &mut io::BufReader<R>) -> Option<Graph> {
-    //! Read csv of 0's and 1's into a graph from given buffered reader.
-    //! Return Some(graph) if it passes validation, otherwise None.
-    let a = reader.lines().map(
-        // turn each row into iterator of ints
-        |r| r.unwrap_or(String::new()).split(",").map(
-            // parse each number into int and collect into vectors
-            |s| s.trim().parse().unwrap_or(0)).map(|v| v == 1)
-        .collect()).collect::<Vec<_>>();
-    if a.verify() {
-        Some(a)
-    } else {
-        None
+pub fn read_csv_with<R: Read>(reader: &mut io::BufReader<R>, loops: LoopPolicy)
+    -> Result<Graph, GraphError> {
+    //! Read csv into a graph with the given loop policy. A 1 on the diagonal is kept, dropped or
+    //! rejected as the policy says.
+    let mut a = read_matrix(reader)?;
+    let n = a.len();
+    let mut pairs = (0..n).flat_map(|i| (i+1..n).map(move |j| (i, j)));
+    if let Some((i, j)) = pairs.find(|&(i, j)| a[i][j] != a[j][i]) {
+        return Err(GraphError::Asymmetric(i, j));
     }
+    apply_loops(&mut a, loops)?;
+    Ok(Graph {
+        rows: a,
+        loops
+    })
 }
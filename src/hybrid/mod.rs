@@ -0,0 +1,414 @@
+use std::borrow::Cow;
+use std::cmp;
+use bitset::BitVec;
+use bitset::kernels;
+use graph;
+use digraph;
+use {GraphRef, GraphMut, BitGraph, Removal, Neighbors, NodeAttrs, EdgeAttrs};
+
+/// Default fraction of n above which a row is promoted to a bitset. A sorted list spends 64
+/// bits per neighbor while a bitset spends one bit per vertex, so they break even at 1/64.
+pub const DEFAULT_DENSITY: f64 = 1.0 / 64.0;
+
+/// Neighborhood of one vertex, either as a sorted list of ids or as a bit row of length n.
+#[derive(Clone, Debug, PartialEq)]
+enum Row {
+    Sparse(Vec<usize>),
+    Dense(BitVec)
+}
+
+impl Row {
+    fn contains(&self, j: usize) -> bool {
+        match *self {
+            Row::Sparse(ref l) => l.binary_search(&j).is_ok(),
+            Row::Dense(ref b) => b[j]
+        }
+    }
+
+    fn degree(&self) -> usize {
+        match *self {
+            Row::Sparse(ref l) => l.len(),
+            Row::Dense(ref b) => kernels::count_ones(b)
+        }
+    }
+
+    fn entries(&self) -> Vec<usize> {
+        match *self {
+            Row::Sparse(ref l) => l.clone(),
+            Row::Dense(ref b) => kernels::ones(b).collect()
+        }
+    }
+
+    fn walk(&self) -> Neighbors<'_> {
+        match *self {
+            Row::Sparse(ref l) => Neighbors::list(l),
+            Row::Dense(ref b) => Neighbors::bits(Cow::Borrowed(b))
+        }
+    }
+
+    fn to_bits(&self, n: usize) -> Cow<'_, BitVec> {
+        match *self {
+            Row::Sparse(ref l) => {
+                let mut b = BitVec::from_elem(n, false);
+                for &j in l.iter() {
+                    b.set(j, true);
+                }
+                Cow::Owned(b)
+            }
+            Row::Dense(ref b) => Cow::Borrowed(b)
+        }
+    }
+
+    fn from_bits(b: BitVec, limit: usize) -> Row {
+        // choose the representation that fits the degree of b
+        if kernels::count_ones(&b) > limit {
+            Row::Dense(b)
+        } else {
+            Row::Sparse(kernels::ones(&b).collect())
+        }
+    }
+
+    fn insert(&mut self, j: usize, n: usize, limit: usize) {
+        let promoted = match *self {
+            Row::Sparse(ref mut l) => {
+                if let Err(pos) = l.binary_search(&j) {
+                    l.insert(pos, j);
+                }
+                if l.len() > limit {
+                    let mut b = BitVec::from_elem(n, false);
+                    for &k in l.iter() {
+                        b.set(k, true);
+                    }
+                    Some(b)
+                } else {
+                    None
+                }
+            }
+            Row::Dense(ref mut b) => {
+                b.set(j, true);
+                None
+            }
+        };
+        if let Some(b) = promoted {
+            *self = Row::Dense(b);
+        }
+    }
+
+    fn remove(&mut self, j: usize, limit: usize) {
+        let demoted = match *self {
+            Row::Sparse(ref mut l) => {
+                if let Ok(pos) = l.binary_search(&j) {
+                    l.remove(pos);
+                }
+                None
+            }
+            Row::Dense(ref mut b) => {
+                b.set(j, false);
+                // demote at half the promotion limit so rows near it do not flip back and forth
+                if kernels::count_ones(b) < limit / 2 {
+                    Some(kernels::ones(b).collect())
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(l) = demoted {
+            *self = Row::Sparse(l);
+        }
+    }
+}
+
+/// Graph whose rows are sorted adjacency lists until their degree passes a density threshold,
+/// and bitsets after. Memory is proportional to the number of edges for sparse graphs, so very
+/// large sparse graphs fit where a dense graph::Graph or digraph::DiGraph would not. Loops are
+/// ignored, as under LoopPolicy::Ignore: add_edge drops them and neither contract nor complement
+/// creates one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HybridGraph {
+    /// Number of vertices.
+    n: usize,
+    /// Whether edges have a direction.
+    directed: bool,
+    /// Fraction of n above which rows are dense.
+    density: f64,
+    /// Rows are dense once their degree exceeds this, density * n.
+    limit: usize,
+    /// out[i] is the out neighborhood of i.
+    out: Vec<Row>,
+    /// inc[i] is the in neighborhood of i, only kept for directed graphs.
+    inc: Vec<Row>
+}
+
+pub fn new(size: usize) -> HybridGraph {
+    //! Construct new undirected HybridGraph with size number of vertices.
+    with_density(size, false, DEFAULT_DENSITY)
+}
+
+pub fn new_directed(size: usize) -> HybridGraph {
+    //! Construct new directed HybridGraph with size number of vertices.
+    with_density(size, true, DEFAULT_DENSITY)
+}
+
+pub fn with_density(size: usize, directed: bool, density: f64) -> HybridGraph {
+    //! Construct new HybridGraph whose rows become bitsets once their degree is more than
+    //! density * size, density in range [0,1].
+    HybridGraph {
+        n: size,
+        directed,
+        density,
+        limit: limit(density, size),
+        out: vec![Row::Sparse(Vec::new()); size],
+        inc: if directed { vec![Row::Sparse(Vec::new()); size] } else { Vec::new() }
+    }
+}
+
+fn limit(density: f64, n: usize) -> usize {
+    cmp::max(1, (density * n as f64) as usize)
+}
+
+impl HybridGraph {
+    fn in_row(&self, id: usize) -> &Row {
+        if self.directed { &self.inc[id] } else { &self.out[id] }
+    }
+
+    fn check(&self, v: usize) {
+        assert!(v < self.n, "vertex {} out of bounds for graph of size {}", v, self.n);
+    }
+
+    pub fn dense_rows(&self) -> usize {
+        //! Return number of out rows currently stored as bitsets.
+        self.out.iter().filter(|r| matches!(**r, Row::Dense(_))).count()
+    }
+
+    fn empty_like(&self, size: usize) -> HybridGraph {
+        HybridGraph {
+            n: size,
+            directed: self.directed,
+            density: self.density,
+            limit: limit(self.density, size),
+            out: vec![Row::Sparse(Vec::new()); size],
+            inc: if self.directed { vec![Row::Sparse(Vec::new()); size] } else { Vec::new() }
+        }
+    }
+}
+
+impl GraphRef for HybridGraph {
+    fn len(&self) -> usize {
+        self.n
+    }
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.out[from].contains(to)
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.in_row(id).to_bits(self.n)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.out[id].to_bits(self.n)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.out[id].walk()
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.in_row(id).walk()
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.out[id].degree()
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.in_row(id).degree()
+    }
+}
+
+impl GraphMut for HybridGraph {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        self.check(fr);
+        self.check(to);
+        if fr == to {
+            return;
+        }
+        let (n, limit) = (self.n, self.limit);
+        self.out[fr].insert(to, n, limit);
+        if self.directed {
+            self.inc[to].insert(fr, n, limit);
+        } else {
+            self.out[to].insert(fr, n, limit);
+        }
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.check(fr);
+        self.check(to);
+        let limit = self.limit;
+        self.out[fr].remove(to, limit);
+        if self.directed {
+            self.inc[to].remove(fr, limit);
+        } else {
+            self.out[to].remove(fr, limit);
+        }
+    }
+    fn add_vertex(&mut self) -> usize {
+        let n = self.n;
+        for r in self.out.iter_mut().chain(self.inc.iter_mut()) {
+            if let Row::Dense(ref mut b) = *r {
+                b.push(false);
+            }
+        }
+        self.out.push(Row::Sparse(Vec::new()));
+        if self.directed {
+            self.inc.push(Row::Sparse(Vec::new()));
+        }
+        self.n += 1;
+        self.limit = limit(self.density, self.n);
+        n
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        let last = self.n - 1;
+        self.check(v);
+        for w in self.out[v].entries() {
+            self.remove_edge(v, w);
+        }
+        for w in self.in_row(v).entries() {
+            self.remove_edge(w, v);
+        }
+        if mode == Removal::Tombstone {
+            return None;
+        }
+        // detach the last vertex, shrink every row by one, then reattach it as v
+        let outs = self.out[last].entries();
+        let ins = self.in_row(last).entries();
+        for &w in outs.iter() {
+            self.remove_edge(last, w);
+        }
+        for &w in ins.iter() {
+            self.remove_edge(w, last);
+        }
+        self.out.pop();
+        if self.directed {
+            self.inc.pop();
+        }
+        for r in self.out.iter_mut().chain(self.inc.iter_mut()) {
+            if let Row::Dense(ref mut b) = *r {
+                b.truncate(last);
+            }
+        }
+        self.n = last;
+        self.limit = limit(self.density, self.n);
+        if v == last {
+            return None;
+        }
+        let relabel = |w: usize| if w == last { v } else { w };
+        for w in outs {
+            self.add_edge(v, relabel(w));
+        }
+        for w in ins {
+            self.add_edge(relabel(w), v);
+        }
+        Some(last)
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        let limit = self.limit;
+        let restrict = |rows: &mut Vec<Row>| {
+            for (i, r) in rows.iter_mut().enumerate() {
+                if !vertices[i] {
+                    *r = Row::Sparse(Vec::new());
+                    continue;
+                }
+                match *r {
+                    Row::Sparse(ref mut l) => l.retain(|&j| vertices[j]),
+                    Row::Dense(ref mut b) => { b.intersect(vertices); }
+                }
+                if r.degree() < limit / 2 {
+                    *r = Row::Sparse(r.entries());
+                }
+            }
+        };
+        restrict(&mut self.out);
+        restrict(&mut self.inc);
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        let (u, v) = e;
+        assert!(self.has_edge(u, v));
+        // the edge between u and v itself would become a loop, so it is only removed
+        for w in self.out[v].entries() {
+            self.remove_edge(v, w);
+            if w != u {
+                self.add_edge(u, w);
+            }
+        }
+        for w in self.in_row(v).entries() {
+            self.remove_edge(w, v);
+            if w != u {
+                self.add_edge(w, u);
+            }
+        }
+    }
+}
+
+impl BitGraph for HybridGraph {
+    fn verify(&self) -> bool {
+        //! Make sure rows are well formed, hold no loops, and in and out rows agree.
+        let n = self.n;
+        let rows_ok = |rows: &[Row]| rows.len() == n && rows.iter().all(|r| match *r {
+            Row::Sparse(ref l) => l.windows(2).all(|w| w[0] < w[1]) && l.iter().all(|&j| j < n),
+            Row::Dense(ref b) => b.len() == n
+        });
+        rows_ok(&self.out) && (!self.directed || rows_ok(&self.inc)) &&
+            (0..n).all(|i| !self.out[i].contains(i)) &&
+            (0..n).all(|i| self.out[i].entries().iter().all(|&j| self.in_row(j).contains(i)))
+    }
+    fn complement(&self) -> HybridGraph {
+        // negate each row and zero the diagonal, then pick a representation for the result
+        let flip = |rows: &[Row]| (0..self.n).map(|i| {
+            let mut b = rows[i].to_bits(self.n).into_owned();
+            b.negate();
+            b.set(i, false);
+            Row::from_bits(b, self.limit)
+        }).collect();
+        let mut new_graph = self.empty_like(self.n);
+        new_graph.out = flip(&self.out);
+        if self.directed {
+            new_graph.inc = flip(&self.inc);
+        }
+        new_graph
+    }
+    fn compressed(&self) -> (HybridGraph, Vec<usize>) {
+        // construct mapping of new indices -> old indices
+        let map: Vec<usize> = (0..self.n).filter(|&idx| self.out[idx].degree() > 0 ||
+                                                 self.in_row(idx).degree() > 0).collect();
+        let mut inverse = vec![0; self.n];
+        for (i, &v) in map.iter().enumerate() {
+            inverse[v] = i;
+        }
+        let mut new_graph = self.empty_like(map.len());
+        for (i, &v) in map.iter().enumerate() {
+            for w in self.out[v].entries() {
+                new_graph.add_edge(i, inverse[w]);
+            }
+        }
+        (new_graph, map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let mut inverse = vec![0; self.n];
+        for (i, &v) in order.iter().enumerate() {
+            inverse[v] = i;
+        }
+        let mut new_graph = self.empty_like(self.n);
+        for (i, &v) in order.iter().enumerate() {
+            for w in self.out[v].entries() {
+                new_graph.add_edge(i, inverse[w]);
+            }
+        }
+        new_graph
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        if self.directed {
+            digraph::serialize_directed_dot(self, node_attrs, edge_attrs)
+        } else {
+            graph::serialize_undirected_dot(self, node_attrs, edge_attrs)
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bitset::BitVec;
+use bitset::kernels;
+use graph::{self, Graph};
+use bipartite::{self, BipartiteGraph};
+use {GraphMut, GraphError};
+
+/// Set system on n vertices, stored as its n x m incidence matrix: rows[v][e] is set if vertex
+/// v belongs to hyperedge e. Hyperedges are numbered 0..m in order of insertion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hypergraph {
+    /// Number of hyperedges.
+    m: usize,
+    /// rows[v] is the set of hyperedges containing v.
+    rows: Vec<BitVec>
+}
+
+pub fn new(size: usize) -> Hypergraph {
+    //! Construct new Hypergraph with size number of vertices and no hyperedges.
+    Hypergraph {
+        m: 0,
+        rows: vec![BitVec::new(); size]
+    }
+}
+
+pub fn from_edges(size: usize, edges: &[&[usize]]) -> Result<Hypergraph, GraphError> {
+    //! Construct a Hypergraph on size vertices with one hyperedge per list of vertices, or fail
+    //! if a vertex is out of bounds.
+    let mut h = new(size);
+    for e in edges.iter() {
+        if let Some(&v) = e.iter().find(|&&v| v >= size) {
+            return Err(GraphError::OutOfBounds { vertex: v, len: size });
+        }
+        h.add_edge(&BitVec::from_fn(size, |v| e.contains(&v)));
+    }
+    Ok(h)
+}
+
+impl Hypergraph {
+    pub fn len(&self) -> usize {
+        //! Return number of vertices in self.
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        //! Return whether self has no vertices.
+        self.rows.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        //! Return number of hyperedges in self.
+        self.m
+    }
+
+    pub fn add_vertex(&mut self) -> usize {
+        //! Add a vertex in no hyperedge and return its id.
+        self.rows.push(BitVec::from_elem(self.m, false));
+        self.rows.len() - 1
+    }
+
+    pub fn add_edge(&mut self, vertices: &BitVec) -> usize {
+        //! Add the hyperedge of the set vertices, |vertices| = |V|, and return its id.
+        assert_eq!(vertices.len(), self.len(), "vertex set must cover the hypergraph");
+        for (v, r) in self.rows.iter_mut().enumerate() {
+            r.push(vertices[v]);
+        }
+        self.m += 1;
+        self.m - 1
+    }
+
+    pub fn remove_edge(&mut self, e: usize) -> Option<usize> {
+        //! Remove hyperedge e. The last hyperedge takes the id e and its old id is returned, or
+        //! None if e was last, as with Removal::SwapRemove.
+        assert!(e < self.m, "hyperedge {} out of bounds for {} hyperedges", e, self.m);
+        for r in self.rows.iter_mut() {
+            r.swap_remove(e);
+        }
+        self.m -= 1;
+        if e < self.m { Some(self.m) } else { None }
+    }
+
+    pub fn contains(&self, e: usize, v: usize) -> bool {
+        //! Decide whether vertex v belongs to hyperedge e.
+        self.rows[v][e]
+    }
+
+    pub fn edge(&self, e: usize) -> BitVec {
+        //! Return the vertices of hyperedge e.
+        BitVec::from_fn(self.len(), |v| self.rows[v][e])
+    }
+
+    pub fn incident(&self, v: usize) -> &BitVec {
+        //! Return the hyperedges containing vertex v.
+        &self.rows[v]
+    }
+
+    pub fn degree(&self, v: usize) -> usize {
+        //! Return number of hyperedges containing vertex v.
+        kernels::count_ones(&self.rows[v])
+    }
+
+    pub fn dual(&self) -> Hypergraph {
+        //! Return the dual of self, whose vertices are the hyperedges of self and whose
+        //! hyperedge v holds the hyperedges of self containing v.
+        Hypergraph {
+            m: self.len(),
+            rows: (0..self.m).map(|e| self.edge(e)).collect()
+        }
+    }
+
+    pub fn primal(&self) -> Graph {
+        //! Return the 2-section of self, where two vertices are joined if some hyperedge
+        //! contains both.
+        let mut g = graph::new(self.len());
+        for (u, a) in self.rows.iter().enumerate() {
+            for (w, b) in self.rows.iter().enumerate().skip(u + 1) {
+                if !kernels::is_disjoint(a, b) {
+                    g.add_edge(u, w);
+                }
+            }
+        }
+        g
+    }
+
+    pub fn incidence_graph(&self) -> BipartiteGraph {
+        //! Return the Levi graph of self: vertices on the left, hyperedges on the right, and an
+        //! edge wherever a vertex belongs to a hyperedge.
+        let n = self.len();
+        let mut b = bipartite::new(n, self.m);
+        for (v, r) in self.rows.iter().enumerate() {
+            for e in kernels::ones(r) {
+                b.add_edge(v, n + e);
+            }
+        }
+        b
+    }
+
+    pub fn is_transversal(&self, vertices: &BitVec) -> bool {
+        //! Decide whether vertices meets every hyperedge, i.e. is a hitting set of self.
+        //! |vertices| = |V|.
+        assert_eq!(vertices.len(), self.len(), "vertex set must cover the hypergraph");
+        let mut hit = BitVec::from_elem(self.m, false);
+        for v in kernels::ones(vertices) {
+            hit.union(&self.rows[v]);
+        }
+        hit.all()
+    }
+
+    pub fn is_minimal_transversal(&self, vertices: &BitVec) -> bool {
+        //! Decide whether vertices is a transversal from which no vertex can be dropped, i.e.
+        //! every member is the only one in some hyperedge.
+        if !self.is_transversal(vertices) {
+            return false;
+        }
+        // edges hit once are those in the union but not hit by a second vertex
+        let mut once = BitVec::from_elem(self.m, false);
+        let mut twice = BitVec::from_elem(self.m, false);
+        for v in kernels::ones(vertices) {
+            let mut both = once.clone();
+            both.intersect(&self.rows[v]);
+            twice.union(&both);
+            once.union(&self.rows[v]);
+        }
+        kernels::ones(vertices).all(|v| kernels::andnot_count(&self.rows[v], &twice) > 0)
+    }
+}
@@ -0,0 +1,362 @@
+//! Undo and redo for any BitGraph. Each mutation is logged with the edges it added and removed
+//! around the vertices it touched, so undoing it only replays that difference, while redoing
+//! it runs the mutation again on the restored graph.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use bitset::BitVec;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, Neighbors};
+use {NodeAttrs, EdgeAttrs};
+
+/// A logged mutation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    AddEdge(usize, usize),
+    RemoveEdge(usize, usize),
+    AddVertex,
+    RemoveVertex(usize, Removal),
+    Induce(BitVec),
+    Contract(usize, usize)
+}
+
+/// A mutation with the edges it changed, keyed as by GraphRef::edges.
+#[derive(Clone, Debug)]
+struct Entry {
+    op: Op,
+    added: Vec<(usize, usize)>,
+    removed: Vec<(usize, usize)>
+}
+
+/// One unit of undo: a single mutation or a committed transaction.
+#[derive(Clone, Debug)]
+enum Step {
+    Single(Entry),
+    Group(Vec<Step>)
+}
+
+/// Graph that logs its mutations so they can be undone, redone, and rolled back to named
+/// checkpoints. add_vertex and remove_vertex are logged too, so vertex ids stay consistent
+/// across undo.
+#[derive(Clone, Debug)]
+pub struct Journaled<G: BitGraph> {
+    graph: G,
+    /// Steps that can be undone, oldest first.
+    done: Vec<Step>,
+    /// Steps that were undone and can be redone, most recently undone last.
+    undone: Vec<Step>,
+    /// Length of done at each checkpoint.
+    checkpoints: HashMap<String, usize>
+}
+
+pub fn new<G: BitGraph>(graph: G) -> Journaled<G> {
+    //! Wrap graph with an empty journal.
+    Journaled {
+        graph,
+        done: Vec::new(),
+        undone: Vec::new(),
+        checkpoints: HashMap::new()
+    }
+}
+
+fn key(directed: bool, u: usize, v: usize) -> (usize, usize) {
+    if directed || u <= v { (u, v) } else { (v, u) }
+}
+
+fn incident<G: GraphRef>(g: &G, vertices: &[usize]) -> Vec<(usize, usize)> {
+    // every edge with an end in vertices, sorted and without repeats
+    let directed = g.is_directed();
+    let mut edges = Vec::new();
+    for &v in vertices.iter().filter(|&&v| v < g.len()) {
+        edges.extend(g.out_neighbor_iter(v).map(|w| key(directed, v, w)));
+        if directed {
+            edges.extend(g.in_neighbor_iter(v).map(|w| (w, v)));
+        }
+    }
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+fn minus(a: &[(usize, usize)], b: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    // elements of sorted a missing from sorted b
+    a.iter().filter(|e| b.binary_search(e).is_err()).cloned().collect()
+}
+
+fn apply<G: BitGraph>(graph: &mut G, op: &Op) {
+    match *op {
+        Op::AddEdge(u, v) => graph.add_edge(u, v),
+        Op::RemoveEdge(u, v) => graph.remove_edge(u, v),
+        Op::AddVertex => { graph.add_vertex(); }
+        Op::RemoveVertex(v, mode) => { graph.remove_vertex(v, mode); }
+        Op::Induce(ref vertices) => graph.induce(vertices),
+        Op::Contract(u, v) => graph.contract((u, v))
+    }
+}
+
+impl<G: BitGraph> Journaled<G> {
+    fn touched(&self, op: &Op) -> Vec<usize> {
+        // vertices whose incident edges op may change
+        match *op {
+            Op::AddEdge(u, v) | Op::RemoveEdge(u, v) | Op::Contract(u, v) => vec![u, v],
+            Op::AddVertex => Vec::new(),
+            Op::RemoveVertex(v, _) => vec![v, self.graph.len() - 1],
+            Op::Induce(ref vertices) => (0..self.len()).filter(|&v| !vertices[v]).collect()
+        }
+    }
+
+    fn snapshot(&self, op: &Op, touched: &[usize]) -> Vec<(usize, usize)> {
+        // the edges op may change, looking only at the edge itself for edge ops
+        match *op {
+            Op::AddEdge(u, v) | Op::RemoveEdge(u, v) => {
+                let e = key(self.is_directed(), u, v);
+                if self.graph.has_edge(u, v) { vec![e] } else { Vec::new() }
+            }
+            _ => incident(&self.graph, touched)
+        }
+    }
+
+    fn record(&mut self, op: Op) {
+        let touched = self.touched(&op);
+        let before = self.snapshot(&op, &touched);
+        apply(&mut self.graph, &op);
+        let after = self.snapshot(&op, &touched);
+        self.push(Entry { added: minus(&after, &before), removed: minus(&before, &after), op });
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.done.push(Step::Single(entry));
+        // a new step ends every redo path and the checkpoints along it
+        self.undone.clear();
+        let n = self.done.len();
+        self.checkpoints.retain(|_, &mut pos| pos < n);
+    }
+
+    fn revert(&mut self, step: &Step) {
+        match *step {
+            Step::Single(ref e) => {
+                match e.op {
+                    Op::AddVertex => {
+                        let last = self.graph.len() - 1;
+                        self.graph.remove_vertex(last, Removal::SwapRemove);
+                    }
+                    Op::RemoveVertex(_, Removal::SwapRemove) => { self.graph.add_vertex(); }
+                    _ => {}
+                }
+                for &(u, v) in e.added.iter() {
+                    self.graph.remove_edge(u, v);
+                }
+                for &(u, v) in e.removed.iter() {
+                    self.graph.add_edge(u, v);
+                }
+            }
+            Step::Group(ref steps) => {
+                for s in steps.iter().rev() {
+                    self.revert(s);
+                }
+            }
+        }
+    }
+
+    fn replay(&mut self, step: &Step) {
+        match *step {
+            Step::Single(ref e) => apply(&mut self.graph, &e.op),
+            Step::Group(ref steps) => {
+                for s in steps.iter() {
+                    self.replay(s);
+                }
+            }
+        }
+    }
+
+    pub fn graph(&self) -> &G {
+        //! Return the current state of the graph.
+        &self.graph
+    }
+
+    pub fn into_inner(self) -> G {
+        //! Drop the journal and return the graph.
+        self.graph
+    }
+
+    pub fn can_undo(&self) -> bool {
+        //! Decide whether there is a step to undo.
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        //! Decide whether there is an undone step to redo.
+        !self.undone.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        //! Undo the last step, a single mutation or a whole transaction. Return false if there
+        //! was nothing to undo.
+        match self.done.pop() {
+            Some(step) => {
+                self.revert(&step);
+                self.undone.push(step);
+                true
+            }
+            None => false
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        //! Redo the last undone step. Return false if there was nothing to redo.
+        match self.undone.pop() {
+            Some(step) => {
+                self.replay(&step);
+                self.done.push(step);
+                true
+            }
+            None => false
+        }
+    }
+
+    pub fn checkpoint<S: Into<String>>(&mut self, name: S) {
+        //! Name the current state, replacing any checkpoint with the same name.
+        self.checkpoints.insert(name.into(), self.done.len());
+    }
+
+    pub fn rollback_to(&mut self, name: &str) -> Result<(), GraphError> {
+        //! Undo, or redo, until the graph is in the state checkpoint name was taken in. Fail if
+        //! there is no such checkpoint, or it was on a redo path that a new step ended.
+        let pos = match self.checkpoints.get(name) {
+            Some(&pos) => pos,
+            None => return Err(GraphError::UnknownCheckpoint(name.to_string()))
+        };
+        while self.done.len() > pos {
+            self.undo();
+        }
+        while self.done.len() < pos {
+            self.redo();
+        }
+        Ok(())
+    }
+
+    pub fn transaction<F, T, E>(&mut self, f: F) -> Result<T, E>
+        where F: FnOnce(&mut Self) -> Result<T, E>
+    {
+        //! Run f on self. If it succeeds, its mutations become one step that undo reverts at
+        //! once. If it fails, they are undone and forgotten, and the error is returned.
+        let start = self.done.len();
+        let redo = self.undone.clone();
+        let result = f(self);
+        // f may have undone past where it started
+        let start = start.min(self.done.len());
+        let changed = self.done.len() > start;
+        if result.is_err() {
+            while self.done.len() > start {
+                self.undo();
+            }
+            if changed {
+                // the failed steps never happened, so what could be redone before still can
+                self.undone = redo;
+            }
+        } else if changed {
+            let steps = self.done.split_off(start);
+            self.done.push(Step::Group(steps));
+        }
+        // checkpoints taken inside f name states that are no longer reachable
+        if changed {
+            self.checkpoints.retain(|_, &mut pos| pos <= start);
+        }
+        result
+    }
+}
+
+impl<G: BitGraph> GraphRef for Journaled<G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.graph.out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.graph.in_degree(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        self.graph.neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(from, to)
+    }
+}
+
+impl<G: BitGraph> GraphMut for Journaled<G> {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        self.record(Op::AddEdge(fr, to));
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        // a rejected edge leaves the graph alone and is not logged
+        self.check_vertex(fr)?;
+        self.check_vertex(to)?;
+        let had = self.graph.has_edge(fr, to);
+        self.graph.try_add_edge(fr, to)?;
+        let added = if !had && self.graph.has_edge(fr, to) {
+            vec![key(self.is_directed(), fr, to)]
+        } else {
+            Vec::new()
+        };
+        self.push(Entry { op: Op::AddEdge(fr, to), added, removed: Vec::new() });
+        Ok(())
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.record(Op::RemoveEdge(fr, to));
+    }
+    fn add_vertex(&mut self) -> usize {
+        self.record(Op::AddVertex);
+        self.graph.len() - 1
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        let last = self.graph.len() - 1;
+        self.record(Op::RemoveVertex(v, mode));
+        if mode == Removal::SwapRemove && v < last { Some(last) } else { None }
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        self.record(Op::Induce(vertices.clone()));
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        self.record(Op::Contract(e.0, e.1));
+    }
+}
+
+impl<G: BitGraph> BitGraph for Journaled<G> {
+    fn verify(&self) -> bool {
+        self.graph.verify()
+    }
+    fn complement(&self) -> Self {
+        //! The complement starts with an empty journal, as do the other derived graphs.
+        new(self.graph.complement())
+    }
+    fn compressed(&self) -> (Self, Vec<usize>) {
+        let (graph, map) = self.graph.compressed();
+        (new(graph), map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        new(self.graph.reordered(order))
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        self.graph.serialize_dot(node_attrs, edge_attrs)
+    }
+}
@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use bitset::BitVec;
+use graph;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, Neighbors};
+use {NodeAttrs, EdgeAttrs};
+
+/// Graph whose vertices carry unique string labels. Labels follow their vertices through every
+/// operation that moves or drops vertices, and are written out as DOT node names and csv
+/// headers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LabeledGraph<G: BitGraph> {
+    /// Topology of the graph.
+    graph: G,
+    /// labels[v] is the label of v.
+    labels: Vec<String>,
+    /// Maps each label back to its vertex.
+    index: HashMap<String, usize>
+}
+
+pub fn new<G: BitGraph>(graph: G) -> LabeledGraph<G> {
+    //! Wrap graph, labeling each vertex by its id.
+    let labels = (0..graph.len()).map(|v| v.to_string()).collect();
+    from_parts(graph, labels)
+}
+
+pub fn with_labels<G, I, S>(graph: G, labels: I) -> Result<LabeledGraph<G>, GraphError>
+    where G: BitGraph, I: IntoIterator<Item = S>, S: Into<String>
+{
+    //! Wrap graph, giving vertex v the v-th label. Fail unless there is one label per vertex
+    //! and no label repeats.
+    let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+    if labels.len() != graph.len() {
+        return Err(GraphError::SizeMismatch { len: labels.len(), expected: graph.len() });
+    }
+    let mut index = HashMap::with_capacity(labels.len());
+    for (v, l) in labels.iter().enumerate() {
+        if index.insert(l.clone(), v).is_some() {
+            return Err(GraphError::DuplicateLabel(l.clone()));
+        }
+    }
+    Ok(LabeledGraph { graph, labels, index })
+}
+
+fn from_parts<G: BitGraph>(graph: G, labels: Vec<String>) -> LabeledGraph<G> {
+    // labels are known to be unique
+    let index = labels.iter().enumerate().map(|(v, l)| (l.clone(), v)).collect();
+    LabeledGraph { graph, labels, index }
+}
+
+impl<G: BitGraph> LabeledGraph<G> {
+    fn lookup(&self, label: &str) -> Result<usize, GraphError> {
+        self.index.get(label).cloned().ok_or_else(|| GraphError::UnknownLabel(label.to_string()))
+    }
+
+    fn fresh_label(&self, v: usize) -> String {
+        // the id of v, primed until no other vertex uses it
+        let mut l = v.to_string();
+        while self.index.contains_key(&l) {
+            l.push('\'');
+        }
+        l
+    }
+
+    pub fn graph(&self) -> &G {
+        //! Return the topology of self.
+        &self.graph
+    }
+
+    pub fn into_inner(self) -> G {
+        //! Drop the labels and return the topology of self.
+        self.graph
+    }
+
+    pub fn labels(&self) -> &[String] {
+        //! Return the labels of all vertices, indexed by vertex.
+        &self.labels
+    }
+
+    pub fn label(&self, v: usize) -> &str {
+        //! Return the label of v.
+        &self.labels[v]
+    }
+
+    pub fn index(&self, label: &str) -> Option<usize> {
+        //! Return the vertex labeled label, if any.
+        self.index.get(label).cloned()
+    }
+
+    pub fn set_label<S: Into<String>>(&mut self, v: usize, label: S) -> Result<(), GraphError> {
+        //! Relabel v, or fail if another vertex has the label.
+        self.check_vertex(v)?;
+        let label = label.into();
+        match self.index.get(&label) {
+            Some(&w) if w != v => return Err(GraphError::DuplicateLabel(label)),
+            _ => {}
+        }
+        self.index.remove(&self.labels[v]);
+        self.index.insert(label.clone(), v);
+        self.labels[v] = label;
+        Ok(())
+    }
+
+    pub fn intern(&mut self, label: &str) -> usize {
+        //! Return the vertex labeled label, adding an isolated vertex for it if there is none.
+        if let Some(&v) = self.index.get(label) {
+            return v;
+        }
+        let v = self.graph.add_vertex();
+        self.labels.push(label.to_string());
+        self.index.insert(label.to_string(), v);
+        v
+    }
+
+    pub fn add_edge_by_label(&mut self, fr: &str, to: &str) {
+        //! Add edge from the vertex labeled fr to the one labeled to, adding either vertex if it
+        //! does not exist yet.
+        let (u, v) = (self.intern(fr), self.intern(to));
+        self.graph.add_edge(u, v);
+    }
+
+    pub fn remove_edge_by_label(&mut self, fr: &str, to: &str) -> Result<(), GraphError> {
+        //! Remove edge from the vertex labeled fr to the one labeled to, or fail if either
+        //! label is unknown.
+        let (u, v) = (self.lookup(fr)?, self.lookup(to)?);
+        self.graph.remove_edge(u, v);
+        Ok(())
+    }
+
+    pub fn has_edge_by_label(&self, fr: &str, to: &str) -> bool {
+        //! Decide whether there is an edge between the vertices labeled fr and to.
+        match (self.index(fr), self.index(to)) {
+            (Some(u), Some(v)) => self.graph.has_edge(u, v),
+            _ => false
+        }
+    }
+
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<(), GraphError> {
+        //! Write the labels as a header line, then the matrix as graph::write_csv does. Labels
+        //! holding a comma or a quote are quoted.
+        let header: Vec<String> = self.labels.iter().map(|l| {
+            if l.contains(',') || l.contains('"') {
+                format!("\"{}\"", l.replace('"', "\"\""))
+            } else {
+                l.clone()
+            }
+        }).collect();
+        writeln!(writer, "{}", header.join(","))?;
+        graph::write_csv(&self.graph, writer)
+    }
+}
+
+impl<G: BitGraph> GraphRef for LabeledGraph<G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.graph.out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.graph.in_degree(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        self.graph.neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(from, to)
+    }
+}
+
+impl<G: BitGraph> GraphMut for LabeledGraph<G> {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        self.graph.add_edge(fr, to);
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.graph.try_add_edge(fr, to)
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.graph.remove_edge(fr, to);
+    }
+    fn add_vertex(&mut self) -> usize {
+        //! The new vertex is labeled by its id, primed if that label is taken.
+        let v = self.graph.add_vertex();
+        let l = self.fresh_label(v);
+        self.index.insert(l.clone(), v);
+        self.labels.push(l);
+        v
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        // a tombstone keeps its label, since the vertex still exists
+        let moved = self.graph.remove_vertex(v, mode);
+        if mode == Removal::SwapRemove {
+            self.index.remove(&self.labels[v]);
+            self.labels.swap_remove(v);
+            if moved.is_some() {
+                self.index.insert(self.labels[v].clone(), v);
+            }
+        }
+        moved
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        self.graph.induce(vertices);
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        self.graph.contract(e);
+    }
+}
+
+impl<G: BitGraph> BitGraph for LabeledGraph<G> {
+    fn verify(&self) -> bool {
+        //! Make sure the topology is valid and labels and index agree.
+        self.graph.verify() && self.labels.len() == self.graph.len() &&
+            self.index.len() == self.labels.len() &&
+            self.labels.iter().enumerate().all(|(v, l)| self.index.get(l) == Some(&v))
+    }
+    fn complement(&self) -> Self {
+        from_parts(self.graph.complement(), self.labels.clone())
+    }
+    fn compressed(&self) -> (Self, Vec<usize>) {
+        let (graph, map) = self.graph.compressed();
+        let labels = map.iter().map(|&v| self.labels[v].clone()).collect();
+        (from_parts(graph, labels), map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let labels = order.iter().map(|&v| self.labels[v].clone()).collect();
+        from_parts(self.graph.reordered(order), labels)
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        //! Nodes are named by their labels; attribute maps are still keyed by vertex id.
+        graph::write_dot(self, self.is_directed(), Some(&self.labels), graph::dot_preamble(),
+                         node_attrs, edge_attrs)
+    }
+}
@@ -0,0 +1,184 @@
+//! Directed graphs with labeled edges, stored as one bit matrix per label. Labels are the ids
+//! 0..k, and a pair of vertices may be joined under any number of them.
+
+use std::borrow::Cow;
+use bitset::BitVec;
+use digraph::{self, DiGraph};
+use {GraphRef, GraphMut, GraphError};
+
+/// How a view joins the layers it covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Combine {
+    /// An edge exists if it exists in any chosen layer.
+    Union,
+    /// An edge exists if it exists in every chosen layer.
+    Intersection
+}
+
+/// Directed graph with one layer of edges per label.
+#[derive(Clone, Debug)]
+pub struct LayeredGraph {
+    /// Number of vertices.
+    n: usize,
+    /// layers[l] holds the edges labeled l.
+    layers: Vec<DiGraph>
+}
+
+/// Read-only graph over a set of layers, joined as given by a Combine.
+pub struct Layers<'a> {
+    graph: &'a LayeredGraph,
+    labels: Vec<usize>,
+    combine: Combine
+}
+
+pub fn new(size: usize, labels: usize) -> LayeredGraph {
+    //! Construct new LayeredGraph with size number of vertices and labels empty layers.
+    LayeredGraph {
+        n: size,
+        layers: (0..labels).map(|_| digraph::new(size)).collect()
+    }
+}
+
+impl LayeredGraph {
+    fn check_label(&self, label: usize) {
+        assert!(label < self.layers.len(), "label {} out of bounds for {} layers", label,
+                self.layers.len());
+    }
+
+    fn join<F>(&self, labels: &[usize], combine: Combine, row: F) -> BitVec
+        where F: Fn(&DiGraph) -> Cow<'_, BitVec>
+    {
+        // fold the chosen rows together, starting from the first
+        assert!(!labels.is_empty(), "at least one label is needed");
+        let mut acc = BitVec::from_elem(self.n, false);
+        for (k, &l) in labels.iter().enumerate() {
+            self.check_label(l);
+            let r = row(&self.layers[l]);
+            if k == 0 || combine == Combine::Union {
+                acc.union(&r);
+            } else {
+                acc.intersect(&r);
+            }
+        }
+        acc
+    }
+
+    pub fn len(&self) -> usize {
+        //! Return number of vertices in self.
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        //! Return whether self has no vertices.
+        self.n == 0
+    }
+
+    pub fn labels(&self) -> usize {
+        //! Return number of layers in self.
+        self.layers.len()
+    }
+
+    pub fn add_label(&mut self) -> usize {
+        //! Append an empty layer and return its label.
+        self.layers.push(digraph::new(self.n));
+        self.layers.len() - 1
+    }
+
+    pub fn layer(&self, label: usize) -> &DiGraph {
+        //! Return the edges labeled label as a DiGraph.
+        self.check_label(label);
+        &self.layers[label]
+    }
+
+    pub fn add_edge(&mut self, fr: usize, to: usize, label: usize) {
+        //! Add edge from fr to to under label.
+        self.check_label(label);
+        self.layers[label].add_edge(fr, to);
+    }
+
+    pub fn try_add_edge(&mut self, fr: usize, to: usize, label: usize)
+        -> Result<(), GraphError>
+    {
+        //! Add edge from fr to to under label, or fail if either vertex is out of bounds.
+        self.check_label(label);
+        self.layers[label].try_add_edge(fr, to)
+    }
+
+    pub fn remove_edge(&mut self, fr: usize, to: usize, label: usize) {
+        //! Remove edge from fr to to under label.
+        self.check_label(label);
+        self.layers[label].remove_edge(fr, to);
+    }
+
+    pub fn has_edge(&self, fr: usize, to: usize, label: usize) -> bool {
+        //! Decide whether fr and to are joined under label.
+        self.check_label(label);
+        self.layers[label].has_edge(fr, to)
+    }
+
+    pub fn edge_labels(&self, fr: usize, to: usize) -> Vec<usize> {
+        //! Return every label joining fr to to.
+        (0..self.layers.len()).filter(|&l| self.layers[l].has_edge(fr, to)).collect()
+    }
+
+    pub fn add_vertex(&mut self) -> usize {
+        //! Add a new isolated vertex to every layer and return its id.
+        for l in self.layers.iter_mut() {
+            l.add_vertex();
+        }
+        self.n += 1;
+        self.n - 1
+    }
+
+    pub fn out_union(&self, id: usize, labels: &[usize]) -> BitVec {
+        //! Return vertices reached from id under any of labels.
+        self.join(labels, Combine::Union, |g| g.out_neighbors(id))
+    }
+
+    pub fn out_intersection(&self, id: usize, labels: &[usize]) -> BitVec {
+        //! Return vertices reached from id under all of labels.
+        self.join(labels, Combine::Intersection, |g| g.out_neighbors(id))
+    }
+
+    pub fn in_union(&self, id: usize, labels: &[usize]) -> BitVec {
+        //! Return vertices reaching id under any of labels.
+        self.join(labels, Combine::Union, |g| g.in_neighbors(id))
+    }
+
+    pub fn in_intersection(&self, id: usize, labels: &[usize]) -> BitVec {
+        //! Return vertices reaching id under all of labels.
+        self.join(labels, Combine::Intersection, |g| g.in_neighbors(id))
+    }
+
+    pub fn view(&self, labels: &[usize], combine: Combine) -> Layers<'_> {
+        //! View the layers in labels joined by combine as one graph, so any function taking a
+        //! GraphRef runs on that relation subset. labels must not be empty.
+        assert!(!labels.is_empty(), "at least one label is needed");
+        for &l in labels.iter() {
+            self.check_label(l);
+        }
+        Layers { graph: self, labels: labels.to_vec(), combine }
+    }
+}
+
+impl<'a> GraphRef for Layers<'a> {
+    fn len(&self) -> usize {
+        self.graph.n
+    }
+    fn is_directed(&self) -> bool {
+        true
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Owned(self.graph.join(&self.labels, self.combine, |g| g.in_neighbors(id)))
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Owned(self.graph.join(&self.labels, self.combine, |g| g.out_neighbors(id)))
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        let mut on = self.labels.iter().map(|&l| self.graph.layers[l].has_edge(from, to));
+        match self.combine {
+            Combine::Union => on.any(|b| b),
+            Combine::Intersection => on.all(|b| b)
+        }
+    }
+}
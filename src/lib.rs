@@ -1,17 +1,180 @@
-#![feature(collections)]
 extern crate rand;
-use std::collections::{BitVec, HashMap};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::slice;
+use bitset::BitVec;
+use bitset::kernels;
+pub use error::GraphError;
 
+pub mod bitset;
+pub mod error;
+mod matrix;
 pub mod graph;
+pub mod attributed;
+pub mod bipartite;
 pub mod digraph;
+pub mod formats;
+pub mod packed;
+pub mod persistent;
+pub mod tournament;
+pub mod hybrid;
+pub mod hypergraph;
+pub mod journal;
+pub mod labeled;
+pub mod layered;
+pub mod views;
+pub mod weighted;
 pub mod utils;
 
-pub trait BitGraph
-    where Self: Clone {
-    /// Verify that this graph is valid.
-    fn verify(&self) -> bool;
+/// How remove_vertex treats the id of the removed vertex.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Removal {
+    /// Move the last vertex into the removed slot and shrink the graph by one.
+    SwapRemove,
+    /// Disconnect the vertex but keep its id, so no other vertex is relabeled.
+    Tombstone
+}
+
+/// How a graph treats self loops (v, v).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum LoopPolicy {
+    /// Loops are errors: try_add_edge fails, add_edge panics and parsers reject them.
+    Forbid,
+    /// Loops are ordinary edges, kept by complement, contract and I/O.
+    Allow,
+    /// Loops are silently dropped wherever they would appear.
+    #[default]
+    Ignore
+}
+
+impl LoopPolicy {
+    pub fn admit(self, v: usize) -> Result<bool, GraphError> {
+        //! Decide whether a loop at v should be stored, or fail if loops are forbidden.
+        match self {
+            LoopPolicy::Forbid => Err(GraphError::SelfLoop(v)),
+            LoopPolicy::Allow => Ok(true),
+            LoopPolicy::Ignore => Ok(false)
+        }
+    }
+}
+
+/// GraphViz attributes for each node, keyed by node id.
+pub type NodeAttrs = HashMap<usize, HashMap<String, String>>;
+/// GraphViz attributes for each edge, keyed by (from, to).
+pub type EdgeAttrs = HashMap<(usize, usize), HashMap<String, String>>;
+
+/// Iterator over the neighbors of a vertex, in increasing order. Walks whatever storage the graph
+/// keeps, a dense row, a sorted list or a packed triangle, so it does not build a row to iterate.
+pub struct Neighbors<'a>(Walk<'a>);
+
+enum Walk<'a> {
+    /// Set bits of a row, borrowed when the graph stores it.
+    Bits(kernels::IntoOnes<'a>),
+    /// A sorted adjacency list.
+    List(slice::Iter<'a, usize>),
+    /// Set bits of a row, each moved up by a fixed offset.
+    Shifted(kernels::Ones<'a>, usize),
+    /// Rows in which a given column is set.
+    Column { rows: &'a [BitVec], col: usize, next: usize },
+    /// Row id of a symmetric matrix of which only the strict upper triangle is packed.
+    Triangle { bits: &'a BitVec, id: usize, next: usize, n: usize },
+    /// Elements of another walk that are in a set.
+    Masked(Box<Neighbors<'a>>, &'a BitVec),
+    /// Ids below n missing from another walk, without skip if it is given.
+    Missing { walk: Box<Neighbors<'a>>, hole: Option<usize>, next: usize, n: usize,
+              skip: Option<usize> }
+}
+
+/// Read side of a graph: enough to run the algorithms in utils. Implemented by every graph type
+/// and by borrowed views such as those in the views module.
+pub trait GraphRef {
     /// Return number of vertices in self.
     fn len(&self) -> usize;
+    /// Return whether edges have a direction. Undirected graphs report each edge from both ends.
+    fn is_directed(&self) -> bool;
+    /// Return whether self has no vertices.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Return how self treats loops. Only graphs with a configurable policy report anything
+    /// other than Ignore.
+    fn loop_policy(&self) -> LoopPolicy {
+        LoopPolicy::Ignore
+    }
+    /// Vector of in neighborhood of a given vertex. Implementations that store rows directly
+    /// borrow them, others build the row on demand.
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec>;
+    /// Vector of out neighborhood of given vertex, borrowed or owned as for in_neighbors.
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec>;
+    /// Vector of all neighbors of given vertex.
+    fn neighbors(&self, id: usize) -> BitVec {
+        let mut i = self.in_neighbors(id).into_owned();
+        i.union(&self.out_neighbors(id));
+        i
+    }
+    /// Test existence of an edge.
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.out_neighbors(from)[to]
+    }
+    /// Iterate over the out neighbors of given vertex.
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        Neighbors::bits(self.out_neighbors(id))
+    }
+    /// Iterate over the in neighbors of given vertex.
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        Neighbors::bits(self.in_neighbors(id))
+    }
+    /// Iterate over every edge as (from, to), in order of from then to. Undirected edges are
+    /// yielded once, with from <= to.
+    fn edges(&self) -> Edges<'_, Self> {
+        Edges { graph: self, from: 0, row: None }
+    }
+    /// Number of out neighbors of given vertex.
+    fn out_degree(&self, id: usize) -> usize {
+        kernels::count_ones(&self.out_neighbors(id))
+    }
+    /// Number of in neighbors of given vertex.
+    fn in_degree(&self, id: usize) -> usize {
+        kernels::count_ones(&self.in_neighbors(id))
+    }
+    /// Number of edges at given vertex: in plus out degree if directed, else out degree. A loop
+    /// counts once per row it appears in.
+    fn degree(&self, id: usize) -> usize {
+        if self.is_directed() {
+            self.in_degree(id) + self.out_degree(id)
+        } else {
+            self.out_degree(id)
+        }
+    }
+    /// Number of edges in self, counting each undirected edge once.
+    fn edge_count(&self) -> usize {
+        let total: usize = (0..self.len()).map(|v| self.out_degree(v)).sum();
+        if self.is_directed() {
+            return total;
+        }
+        // every edge is in two rows except loops, which are in one
+        let loops = (0..self.len()).filter(|&v| self.has_edge(v, v)).count();
+        (total + loops) / 2
+    }
+    /// Degrees of all vertices in nonincreasing order.
+    fn degree_sequence(&self) -> Vec<usize> {
+        let mut d: Vec<usize> = (0..self.len()).map(|v| self.degree(v)).collect();
+        d.sort_unstable_by(|a, b| b.cmp(a));
+        d
+    }
+    /// Return an error unless v is a vertex of self.
+    fn check_vertex(&self, v: usize) -> Result<(), GraphError> {
+        if v < self.len() {
+            Ok(())
+        } else {
+            Err(GraphError::OutOfBounds { vertex: v, len: self.len() })
+        }
+    }
+}
+
+/// In-place mutations of a graph.
+pub trait GraphMut: GraphRef {
     /// Add edge from fr to to.
     fn add_edge(&mut self, fr: usize, to: usize);
     /// Add edges from fr to each element in tovec.
@@ -28,22 +191,13 @@ pub trait BitGraph
             self.remove_edge(fr, i);
         }
     }
-    /// Vector of in neighborhood of a given vertex.
-    fn in_neighbors(&self, id: usize) -> &BitVec;
-    /// Vector of out neighborhood of given vertex.
-    fn out_neighbors(&self, id: usize) -> &BitVec;
-    /// Vector of all neighbors of given vertex.
-    fn neighbors(&self, id: usize) -> BitVec {
-        let mut i = self.in_neighbors(id).clone();
-        i.union(self.out_neighbors(id));
-        i
-    }
-    /// Test existence of an edge.
-    fn has_edge(&self, from: usize, to: usize) -> bool {
-        self.out_neighbors(from)[to]
-    }
-    /// Return complement of the graph.
-    fn complement(&self) -> Self;
+    /// Add a new isolated vertex and return its id. Row storage grows geometrically, so adding
+    /// vertices one at a time takes amortized O(n) bit operations each.
+    fn add_vertex(&mut self) -> usize;
+    /// Remove vertex v. With Removal::SwapRemove the last vertex takes the id v and its old id is
+    /// returned (None if v was last). With Removal::Tombstone v only loses its edges and None is
+    /// returned.
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize>;
     /// Induce subgraph of given vertices. Does not change size of graph, but disconnects vertices
     /// not set in given set.
     fn induce(&mut self, vertices: &BitVec);
@@ -51,6 +205,164 @@ pub trait BitGraph
     /// v become incident to u instead, and v is disconnected from the graph. e must be an edge in
     /// the graph.
     fn contract(&mut self, e: (usize, usize));
+    /// Add edge from fr to to, or fail if either vertex is out of bounds.
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.check_vertex(fr)?;
+        self.check_vertex(to)?;
+        self.add_edge(fr, to);
+        Ok(())
+    }
+    /// Add edges from fr to each element in tovec. Fail without changing self if any vertex is
+    /// out of bounds, and stop at the first edge try_add_edge rejects.
+    fn try_add_edges(&mut self, fr: usize, tovec: &[usize]) -> Result<(), GraphError> {
+        self.check_vertex(fr)?;
+        for &i in tovec.iter() {
+            self.check_vertex(i)?;
+        }
+        for &i in tovec.iter() {
+            self.try_add_edge(fr, i)?;
+        }
+        Ok(())
+    }
+    /// Remove edge from fr to to, or fail if either vertex is out of bounds.
+    fn try_remove_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        self.check_vertex(fr)?;
+        self.check_vertex(to)?;
+        self.remove_edge(fr, to);
+        Ok(())
+    }
+    /// Remove vertex v as in remove_vertex, or fail if v is out of bounds.
+    fn try_remove_vertex(&mut self, v: usize, mode: Removal) -> Result<Option<usize>, GraphError> {
+        self.check_vertex(v)?;
+        Ok(self.remove_vertex(v, mode))
+    }
+    /// Induce subgraph of given vertices, or fail if |vertices| != |V|.
+    fn try_induce(&mut self, vertices: &BitVec) -> Result<(), GraphError> {
+        if vertices.len() != self.len() {
+            return Err(GraphError::SizeMismatch { len: vertices.len(), expected: self.len() });
+        }
+        self.induce(vertices);
+        Ok(())
+    }
+    /// Contract the edge e = (u->v), or fail if it is not an edge of self.
+    fn try_contract(&mut self, e: (usize, usize)) -> Result<(), GraphError> {
+        self.check_vertex(e.0)?;
+        self.check_vertex(e.1)?;
+        if !self.has_edge(e.0, e.1) {
+            return Err(GraphError::MissingEdge(e.0, e.1));
+        }
+        self.contract(e);
+        Ok(())
+    }
+}
+
+/// Iterator over the edges of a graph, built by GraphRef::edges.
+pub struct Edges<'a, G: 'a + GraphRef + ?Sized> {
+    graph: &'a G,
+    from: usize,
+    row: Option<Neighbors<'a>>
+}
+
+impl<'a, G: GraphRef + ?Sized> Iterator for Edges<'a, G> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<(usize, usize)> {
+        let directed = self.graph.is_directed();
+        loop {
+            if let Some(ref mut row) = self.row {
+                let from = self.from;
+                if let Some(to) = row.find(|&to| directed || from <= to) {
+                    return Some((from, to));
+                }
+                self.from += 1;
+            }
+            if self.from >= self.graph.len() {
+                return None;
+            }
+            self.row = Some(self.graph.out_neighbor_iter(self.from));
+        }
+    }
+}
+
+impl<'a> Neighbors<'a> {
+    pub(crate) fn bits(row: Cow<'a, BitVec>) -> Neighbors<'a> {
+        Neighbors(Walk::Bits(kernels::into_ones(row)))
+    }
+
+    pub(crate) fn list(list: &'a [usize]) -> Neighbors<'a> {
+        Neighbors(Walk::List(list.iter()))
+    }
+
+    pub(crate) fn shifted(row: &'a BitVec, offset: usize) -> Neighbors<'a> {
+        Neighbors(Walk::Shifted(kernels::ones(row), offset))
+    }
+
+    pub(crate) fn column(rows: &'a [BitVec], col: usize) -> Neighbors<'a> {
+        Neighbors(Walk::Column { rows, col, next: 0 })
+    }
+
+    pub(crate) fn triangle(bits: &'a BitVec, id: usize, n: usize) -> Neighbors<'a> {
+        Neighbors(Walk::Triangle { bits, id, next: 0, n })
+    }
+
+    pub(crate) fn masked(self, set: &'a BitVec) -> Neighbors<'a> {
+        Neighbors(Walk::Masked(Box::new(self), set))
+    }
+
+    pub(crate) fn missing(mut self, n: usize, skip: Option<usize>) -> Neighbors<'a> {
+        let hole = self.next();
+        Neighbors(Walk::Missing { walk: Box::new(self), hole, next: 0, n, skip })
+    }
+}
+
+impl<'a> Iterator for Neighbors<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self.0 {
+            Walk::Bits(ref mut ones) => ones.next(),
+            Walk::List(ref mut list) => list.next().cloned(),
+            Walk::Shifted(ref mut ones, offset) => ones.next().map(|j| j + offset),
+            Walk::Column { rows, col, ref mut next } => {
+                let i = (*next..rows.len()).find(|&i| rows[i][col])?;
+                *next = i + 1;
+                Some(i)
+            }
+            Walk::Triangle { bits, id, ref mut next, n } => {
+                // column id above the diagonal, then row id to the right of it
+                let j = (*next..n).find(|&j| match j.cmp(&id) {
+                    Ordering::Less => bits[packed::triangle(id) + j],
+                    Ordering::Equal => false,
+                    Ordering::Greater => bits[packed::triangle(j) + id]
+                })?;
+                *next = j + 1;
+                Some(j)
+            }
+            Walk::Masked(ref mut walk, set) => walk.find(|&j| set[j]),
+            Walk::Missing { ref mut walk, ref mut hole, ref mut next, n, skip } => {
+                while *next < n {
+                    let j = *next;
+                    *next += 1;
+                    while hole.is_some_and(|h| h < j) {
+                        *hole = walk.next();
+                    }
+                    if *hole != Some(j) && skip != Some(j) {
+                        return Some(j);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+/// An owned graph: mutable, cloneable, and able to build transformed copies of itself.
+pub trait BitGraph: GraphMut
+    where Self: Clone {
+    /// Verify that this graph is valid.
+    fn verify(&self) -> bool;
+    /// Return complement of the graph.
+    fn complement(&self) -> Self;
     /// Return copy of self with all disconnected vertices removed and return a vector v where
     /// v[i_new]=i_old, a mapping from new indices to old indices.
     fn compressed(&self) -> (Self, Vec<usize>);
@@ -69,8 +381,153 @@ pub trait BitGraph
     fn reordered(&self, order: &[usize]) -> Self;
     /// Serialize the graph to DOT GraphViz format, where optional attribute maps contain valid
     /// GraphViz properties.
-    fn serialize_dot(&self, node_attrs: Option<&HashMap<usize, HashMap<String, String>>>,
-                            edge_attrs: Option<&HashMap<(usize, usize), HashMap<String, String>>>)
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
         -> String;
 }
 
+impl<G: GraphRef + ?Sized> GraphRef for &G {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    fn is_directed(&self) -> bool {
+        (**self).is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        (**self).loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        (**self).in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        (**self).out_neighbors(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        (**self).neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        (**self).has_edge(from, to)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        (**self).out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        (**self).in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        (**self).out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        (**self).in_degree(id)
+    }
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+    fn degree(&self, id: usize) -> usize {
+        (**self).degree(id)
+    }
+    fn edge_count(&self) -> usize {
+        (**self).edge_count()
+    }
+    fn degree_sequence(&self) -> Vec<usize> {
+        (**self).degree_sequence()
+    }
+    fn check_vertex(&self, v: usize) -> Result<(), GraphError> {
+        (**self).check_vertex(v)
+    }
+}
+
+impl<G: GraphRef + ?Sized> GraphRef for &mut G {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    fn is_directed(&self) -> bool {
+        (**self).is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        (**self).loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        (**self).in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        (**self).out_neighbors(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        (**self).neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        (**self).has_edge(from, to)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        (**self).out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        (**self).in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        (**self).out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        (**self).in_degree(id)
+    }
+    fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+    fn degree(&self, id: usize) -> usize {
+        (**self).degree(id)
+    }
+    fn edge_count(&self) -> usize {
+        (**self).edge_count()
+    }
+    fn degree_sequence(&self) -> Vec<usize> {
+        (**self).degree_sequence()
+    }
+    fn check_vertex(&self, v: usize) -> Result<(), GraphError> {
+        (**self).check_vertex(v)
+    }
+}
+
+impl<G: GraphMut + ?Sized> GraphMut for &mut G {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        (**self).add_edge(fr, to)
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        (**self).remove_edge(fr, to)
+    }
+    fn add_vertex(&mut self) -> usize {
+        (**self).add_vertex()
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        (**self).remove_vertex(v, mode)
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        (**self).induce(vertices)
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        (**self).contract(e)
+    }
+    fn add_edges(&mut self, fr: usize, tovec: &[usize]) {
+        (**self).add_edges(fr, tovec)
+    }
+    fn remove_edges(&mut self, fr: usize, tovec: &[usize]) {
+        (**self).remove_edges(fr, tovec)
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        (**self).try_add_edge(fr, to)
+    }
+    fn try_add_edges(&mut self, fr: usize, tovec: &[usize]) -> Result<(), GraphError> {
+        (**self).try_add_edges(fr, tovec)
+    }
+    fn try_remove_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        (**self).try_remove_edge(fr, to)
+    }
+    fn try_remove_vertex(&mut self, v: usize, mode: Removal) -> Result<Option<usize>, GraphError> {
+        (**self).try_remove_vertex(v, mode)
+    }
+    fn try_induce(&mut self, vertices: &BitVec) -> Result<(), GraphError> {
+        (**self).try_induce(vertices)
+    }
+    fn try_contract(&mut self, e: (usize, usize)) -> Result<(), GraphError> {
+        (**self).try_contract(e)
+    }
+}
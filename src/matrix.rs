@@ -0,0 +1,99 @@
+//! Operations on square bit matrices, shared by graph::Graph and the two matrices of
+//! digraph::DiGraph.
+
+use std::borrow::Borrow;
+use bitset::BitVec;
+use Removal;
+
+pub fn new(size: usize) -> Vec<BitVec> {
+    //! Construct a size x size matrix of zeros.
+    vec![BitVec::from_elem(size, false); size]
+}
+
+pub fn is_square(rows: &[BitVec]) -> bool {
+    //! Decide whether every row has one entry per row.
+    rows.iter().all(|r| r.len() == rows.len())
+}
+
+pub fn has_diagonal(rows: &[BitVec]) -> Option<usize> {
+    //! Return the first i with rows[i][i] set.
+    (0..rows.len()).find(|&i| rows[i][i])
+}
+
+pub fn clear_diagonal(rows: &mut [BitVec]) {
+    //! Zero every entry rows[i][i].
+    for (i, r) in rows.iter_mut().enumerate() {
+        r.set(i, false);
+    }
+}
+
+pub fn transpose(rows: &[BitVec]) -> Vec<BitVec> {
+    //! Return the transpose of rows.
+    (0..rows.len()).map(|i| BitVec::from_fn(rows.len(), |j| rows[j][i])).collect()
+}
+
+pub fn complement<R: Borrow<BitVec>>(rows: &[R], keep_diagonal: bool) -> Vec<BitVec> {
+    //! Negate each row. Unless keep_diagonal, zero the diagonal instead of negating it.
+    let mut new_rows: Vec<BitVec> = rows.iter().map(|r| r.borrow().clone()).collect();
+    for (i, r) in new_rows.iter_mut().enumerate() {
+        r.negate();
+        if !keep_diagonal {
+            r.set(i, false);
+        }
+    }
+    new_rows
+}
+
+pub fn select<R: Borrow<BitVec>>(rows: &[R], order: &[usize]) -> Vec<BitVec> {
+    //! Return the matrix whose entry (v, w) is rows[order[v]][order[w]]. order may be shorter
+    //! than rows, which keeps only the listed vertices. Rows may be owned or shared.
+    order.iter().map(|&v| {
+        let row = rows[v].borrow();
+        BitVec::from_fn(order.len(), |w| row[order[w]])
+    }).collect()
+}
+
+pub fn induce(rows: &mut [BitVec], vertices: &BitVec) {
+    //! Zero every row and column not in vertices.
+    for (i, r) in rows.iter_mut().enumerate() {
+        if vertices[i] {
+            r.intersect(vertices);
+        } else {
+            r.clear();
+        }
+    }
+}
+
+pub fn add_vertex(rows: &mut Vec<BitVec>) -> usize {
+    //! Append a zero row and column and return the new index.
+    let n = rows.len();
+    for r in rows.iter_mut() {
+        r.push(false);
+    }
+    // give the new row the capacity of the others so all rows grow together
+    let mut row = BitVec::with_capacity(rows.first().map_or(n + 1, BitVec::capacity));
+    row.grow(n + 1, false);
+    rows.push(row);
+    n
+}
+
+pub fn remove_vertex(rows: &mut Vec<BitVec>, v: usize, mode: Removal) -> Option<usize> {
+    //! Remove row and column v as described by BitGraph::remove_vertex.
+    let last = rows.len() - 1;
+    match mode {
+        Removal::SwapRemove => {
+            rows.swap_remove(v);
+            for r in rows.iter_mut() {
+                r.swap_remove(v);
+            }
+            if v < last { Some(last) } else { None }
+        }
+        Removal::Tombstone => {
+            rows[v].clear();
+            for r in rows.iter_mut() {
+                r.set(v, false);
+            }
+            None
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::borrow::Cow;
+use bitset::BitVec;
+use bitset::kernels;
+use graph::{self, Graph};
+use {GraphRef, GraphMut, BitGraph, Removal, Neighbors, NodeAttrs, EdgeAttrs};
+
+/// Undirected graph which stores only the strict upper triangle of its adjacency matrix, using
+/// half the memory of graph::Graph. Self loops cannot be represented and are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedGraph {
+    /// Number of vertices.
+    n: usize,
+    /// Columns of the strict upper triangle laid end to end: column j holds the edges (i < j, j),
+    /// so adding a vertex only appends a column.
+    bits: BitVec
+}
+
+pub(crate) fn triangle(n: usize) -> usize {
+    n * n.saturating_sub(1) / 2
+}
+
+pub fn new(size: usize) -> PackedGraph {
+    //! Construct new PackedGraph with size number of vertices.
+    PackedGraph {
+        n: size,
+        bits: BitVec::from_elem(triangle(size), false)
+    }
+}
+
+pub fn complete(n: usize) -> PackedGraph {
+    //! Construct K_n.
+    PackedGraph {
+        n,
+        bits: BitVec::from_elem(triangle(n), true)
+    }
+}
+
+impl PackedGraph {
+    fn index(&self, i: usize, j: usize) -> usize {
+        // position of the pair in the packed triangle, with i < j
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        assert!(j < self.n, "vertex {} out of bounds for graph of size {}", j, self.n);
+        triangle(j) + i
+    }
+
+    fn row(&self, id: usize) -> BitVec {
+        // column id above the diagonal, then row id to the right of it
+        BitVec::from_fn(self.n, |j| j != id && self.bits[self.index(id, j)])
+    }
+
+    pub fn from_graph(g: &Graph) -> PackedGraph {
+        //! Pack the upper triangle of g. Loops and asymmetric entries below the diagonal are
+        //! dropped.
+        let mut p = new(g.len());
+        for i in 0..g.len() {
+            for j in g.out_neighbor_iter(i).filter(|&j| j > i) {
+                p.add_edge(i, j);
+            }
+        }
+        p
+    }
+
+    pub fn to_graph(&self) -> Graph {
+        //! Expand self into a full symmetric matrix.
+        let mut g = graph::new(self.n);
+        for i in 0..self.n {
+            for j in kernels::ones(&self.row(i)).filter(|&j| j > i) {
+                g.add_edge(i, j);
+            }
+        }
+        g
+    }
+}
+
+impl GraphRef for PackedGraph {
+    fn len(&self) -> usize {
+        self.n
+    }
+    fn is_directed(&self) -> bool {
+        false
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        from != to && self.bits[self.index(from, to)]
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        self.row(id)
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Owned(self.row(id))
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Owned(self.row(id))
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        assert!(id < self.n, "vertex {} out of bounds for graph of size {}", id, self.n);
+        Neighbors::triangle(&self.bits, id, self.n)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.out_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.out_neighbor_iter(id).count()
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.out_degree(id)
+    }
+}
+
+impl GraphMut for PackedGraph {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        if fr != to {
+            let idx = self.index(fr, to);
+            self.bits.set(idx, true);
+        }
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        if fr != to {
+            let idx = self.index(fr, to);
+            self.bits.set(idx, false);
+        }
+    }
+    fn add_vertex(&mut self) -> usize {
+        let n = self.n;
+        self.bits.grow(n, false);
+        self.n += 1;
+        n
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        let last = self.n - 1;
+        for w in kernels::ones(&self.row(v)) {
+            self.remove_edge(v, w);
+        }
+        if mode == Removal::Tombstone {
+            return None;
+        }
+        // move the last column into v, then drop it
+        for w in kernels::ones(&self.row(last)).filter(|&w| w != v) {
+            self.add_edge(v, w);
+        }
+        self.bits.truncate(triangle(last));
+        self.n = last;
+        if v < last { Some(last) } else { None }
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        for i in 0..self.n {
+            for j in i+1..self.n {
+                if !(vertices[i] && vertices[j]) {
+                    self.remove_edge(i, j);
+                }
+            }
+        }
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        let (fr, to) = e;
+        assert!(self.has_edge(fr, to));
+        for w in kernels::ones(&self.row(to)) {
+            self.remove_edge(to, w);
+            self.add_edge(fr, w);
+        }
+    }
+}
+
+impl BitGraph for PackedGraph {
+    fn verify(&self) -> bool {
+        //! Make sure the triangle has the right size; symmetry holds by construction.
+        self.bits.len() == triangle(self.n)
+    }
+    fn complement(&self) -> PackedGraph {
+        // the diagonal is not stored, so negating the triangle is enough
+        let mut new_graph = self.clone();
+        new_graph.bits.negate();
+        new_graph
+    }
+    fn compressed(&self) -> (PackedGraph, Vec<usize>) {
+        // construct mapping of new indices -> old indices
+        let map: Vec<usize> = (0..self.n).filter(|&idx| self.row(idx).any()).collect();
+        let mut new_graph = new(map.len());
+        for (i, &u) in map.iter().enumerate() {
+            for (j, &v) in map.iter().enumerate().skip(i + 1) {
+                if self.has_edge(u, v) {
+                    new_graph.add_edge(i, j);
+                }
+            }
+        }
+        (new_graph, map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let mut new_graph = new(self.n);
+        for v in 0..self.n {
+            for w in v+1..self.n {
+                if self.has_edge(order[v], order[w]) {
+                    new_graph.add_edge(v, w);
+                }
+            }
+        }
+        new_graph
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        graph::serialize_undirected_dot(self, node_attrs, edge_attrs)
+    }
+}
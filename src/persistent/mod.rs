@@ -0,0 +1,203 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use bitset::BitVec;
+use bitset::kernels;
+use graph;
+use matrix;
+use {GraphRef, GraphMut, BitGraph, Removal, NodeAttrs, EdgeAttrs};
+
+/// Graph whose rows are reference counted and copied only when written. Cloning costs n pointer
+/// copies, and each edge change copies the one or two rows it touches if they are shared, so
+/// many snapshots that differ in a few rows share the rest. Loops are ignored, as under
+/// LoopPolicy::Ignore.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PersistentGraph {
+    /// Whether edges have a direction.
+    directed: bool,
+    /// out[i] is the out neighborhood of i.
+    out: Vec<Arc<BitVec>>,
+    /// inc[i] is the in neighborhood of i, only kept for directed graphs.
+    inc: Vec<Arc<BitVec>>
+}
+
+pub fn new(size: usize) -> PersistentGraph {
+    //! Construct new undirected PersistentGraph with size number of vertices.
+    from_rows(false, matrix::new(size), Vec::new())
+}
+
+pub fn new_directed(size: usize) -> PersistentGraph {
+    //! Construct new directed PersistentGraph with size number of vertices.
+    from_rows(true, matrix::new(size), matrix::new(size))
+}
+
+pub fn from_graph<G: GraphRef>(g: &G) -> PersistentGraph {
+    //! Copy the rows of g, directed if g is. Loops are dropped.
+    let rows = |incoming: bool| {
+        let mut rows: Vec<BitVec> = (0..g.len()).map(|i| {
+            if incoming { g.in_neighbors(i) } else { g.out_neighbors(i) }.into_owned()
+        }).collect();
+        matrix::clear_diagonal(&mut rows);
+        rows
+    };
+    let inc = if g.is_directed() { rows(true) } else { Vec::new() };
+    from_rows(g.is_directed(), rows(false), inc)
+}
+
+fn from_rows(directed: bool, out: Vec<BitVec>, inc: Vec<BitVec>) -> PersistentGraph {
+    PersistentGraph {
+        directed,
+        out: out.into_iter().map(Arc::new).collect(),
+        inc: inc.into_iter().map(Arc::new).collect()
+    }
+}
+
+fn unshare(rows: &[Arc<BitVec>]) -> Vec<BitVec> {
+    rows.iter().map(|r| (**r).clone()).collect()
+}
+
+impl PersistentGraph {
+    fn write(&mut self, fr: usize, to: usize, value: bool) {
+        // touch a row only if the bit changes, so unchanged rows stay shared
+        if fr == to || self.out[fr][to] == value {
+            return;
+        }
+        Arc::make_mut(&mut self.out[fr]).set(to, value);
+        if self.directed {
+            Arc::make_mut(&mut self.inc[to]).set(fr, value);
+        } else {
+            Arc::make_mut(&mut self.out[to]).set(fr, value);
+        }
+    }
+
+    fn in_rows(&self) -> &[Arc<BitVec>] {
+        if self.directed { &self.inc } else { &self.out }
+    }
+
+    pub fn shared_rows(&self, other: &PersistentGraph) -> usize {
+        //! Return number of out rows self shares with other rather than holding its own copy.
+        self.out.iter().zip(other.out.iter()).filter(|&(a, b)| Arc::ptr_eq(a, b)).count()
+    }
+}
+
+impl GraphRef for PersistentGraph {
+    fn len(&self) -> usize {
+        self.out.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.in_rows()[id])
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        Cow::Borrowed(&self.out[id])
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.out[from][to]
+    }
+}
+
+impl GraphMut for PersistentGraph {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        self.write(fr, to, true);
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.write(fr, to, false);
+    }
+    fn add_vertex(&mut self) -> usize {
+        //! Every row grows by a bit, so every row is copied.
+        let mut out = unshare(&self.out);
+        let v = matrix::add_vertex(&mut out);
+        self.out = out.into_iter().map(Arc::new).collect();
+        if self.directed {
+            let mut inc = unshare(&self.inc);
+            matrix::add_vertex(&mut inc);
+            self.inc = inc.into_iter().map(Arc::new).collect();
+        }
+        v
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        //! A tombstone copies only the rows of v's neighbors; swap removal shrinks and copies
+        //! every row.
+        let outs: Vec<usize> = self.out_neighbor_iter(v).collect();
+        let ins: Vec<usize> = self.in_neighbor_iter(v).collect();
+        for w in outs {
+            self.remove_edge(v, w);
+        }
+        for w in ins {
+            self.remove_edge(w, v);
+        }
+        if mode == Removal::Tombstone {
+            return None;
+        }
+        let mut out = unshare(&self.out);
+        let moved = matrix::remove_vertex(&mut out, v, mode);
+        self.out = out.into_iter().map(Arc::new).collect();
+        if self.directed {
+            let mut inc = unshare(&self.inc);
+            matrix::remove_vertex(&mut inc, v, mode);
+            self.inc = inc.into_iter().map(Arc::new).collect();
+        }
+        moved
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        let restrict = |rows: &mut Vec<Arc<BitVec>>| {
+            for (i, r) in rows.iter_mut().enumerate() {
+                if !vertices[i] {
+                    if r.any() {
+                        Arc::make_mut(r).clear();
+                    }
+                } else if !kernels::is_subset(r, vertices) {
+                    Arc::make_mut(r).intersect(vertices);
+                }
+            }
+        };
+        restrict(&mut self.out);
+        restrict(&mut self.inc);
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        let (u, v) = e;
+        assert!(self.has_edge(u, v));
+        // the edge itself would become a loop at u and is dropped
+        let outs: Vec<usize> = self.out_neighbor_iter(v).collect();
+        let ins: Vec<usize> = self.in_neighbor_iter(v).collect();
+        for w in outs {
+            self.remove_edge(v, w);
+            self.add_edge(u, w);
+        }
+        for w in ins {
+            self.remove_edge(w, v);
+            self.add_edge(w, u);
+        }
+    }
+}
+
+impl BitGraph for PersistentGraph {
+    fn verify(&self) -> bool {
+        //! Make sure rows are square, in and out rows agree, and there are no loops.
+        let n = self.len();
+        let in_rows = self.in_rows();
+        in_rows.len() == n && self.out.iter().chain(in_rows.iter()).all(|r| r.len() == n) &&
+            (0..n).all(|i| !self.out[i][i] && (0..n).all(|j| self.out[i][j] == in_rows[j][i]))
+    }
+    fn complement(&self) -> PersistentGraph {
+        let inc = if self.directed { matrix::complement(&self.inc, false) } else { Vec::new() };
+        from_rows(self.directed, matrix::complement(&self.out, false), inc)
+    }
+    fn compressed(&self) -> (PersistentGraph, Vec<usize>) {
+        let map: Vec<usize> = (0..self.len())
+            .filter(|&v| self.out[v].any() || self.in_rows()[v].any()).collect();
+        let inc = if self.directed { matrix::select(&self.inc, &map) } else { Vec::new() };
+        (from_rows(self.directed, matrix::select(&self.out, &map), inc), map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let inc = if self.directed { matrix::select(&self.inc, order) } else { Vec::new() };
+        from_rows(self.directed, matrix::select(&self.out, order), inc)
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        graph::write_dot(self, self.directed, None, graph::dot_preamble(), node_attrs,
+                         edge_attrs)
+    }
+}
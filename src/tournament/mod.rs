@@ -0,0 +1,205 @@
+use std::io;
+use std::io::Read;
+use std::borrow::Cow;
+use rand;
+use bitset::BitVec;
+use bitset::kernels;
+use digraph::{self, DiGraph};
+use {utils, views};
+use {GraphRef, GraphMut, GraphError};
+
+/// Orientation of a complete graph: every pair of distinct vertices is joined by exactly one
+/// arc, and there are no loops. Arcs can only be reversed, never added or removed alone.
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    graph: DiGraph
+}
+
+pub fn from_digraph(graph: DiGraph) -> Result<Tournament, GraphError> {
+    //! Check that graph has exactly one arc per pair and no loops, and wrap it.
+    let n = graph.len();
+    for u in 0..n {
+        if graph.has_edge(u, u) {
+            return Err(GraphError::SelfLoop(u));
+        }
+        for v in u+1..n {
+            let arcs = graph.has_edge(u, v) as usize + graph.has_edge(v, u) as usize;
+            if arcs != 1 {
+                return Err(GraphError::ArcCount { pair: (u, v), arcs });
+            }
+        }
+    }
+    Ok(Tournament { graph })
+}
+
+pub fn transitive(n: usize) -> Tournament {
+    //! Construct the transitive tournament on n vertices, where i beats j whenever i < j.
+    let mut graph = digraph::new(n);
+    for i in 0..n {
+        for j in i+1..n {
+            graph.add_edge(i, j);
+        }
+    }
+    Tournament { graph }
+}
+
+pub fn random(n: usize) -> Tournament {
+    //! Construct a tournament on n vertices with each arc oriented by a fair coin.
+    let mut graph = digraph::new(n);
+    for i in 0..n {
+        for j in i+1..n {
+            if rand::random::<bool>() {
+                graph.add_edge(i, j);
+            } else {
+                graph.add_edge(j, i);
+            }
+        }
+    }
+    Tournament { graph }
+}
+
+pub fn read_csv<R: Read>(reader: &mut io::BufReader<R>) -> Result<Tournament, GraphError> {
+    //! Read csv of 0's and 1's as digraph::read_csv does, then check it is a tournament.
+    from_digraph(digraph::read_csv(reader)?)
+}
+
+impl Tournament {
+    pub fn digraph(&self) -> &DiGraph {
+        //! Return the arcs of self as a DiGraph.
+        &self.graph
+    }
+
+    pub fn into_digraph(self) -> DiGraph {
+        //! Unwrap self into its DiGraph.
+        self.graph
+    }
+
+    pub fn reverse(&mut self, u: usize, v: usize) {
+        //! Reverse the arc between distinct vertices u and v.
+        assert!(u != v, "a tournament has no loops");
+        if self.graph.has_edge(u, v) {
+            self.graph.remove_edge(u, v);
+            self.graph.add_edge(v, u);
+        } else {
+            self.graph.remove_edge(v, u);
+            self.graph.add_edge(u, v);
+        }
+    }
+
+    pub fn score(&self, v: usize) -> usize {
+        //! Return number of vertices v beats.
+        self.graph.out_degree(v)
+    }
+
+    pub fn scores(&self) -> Vec<usize> {
+        //! Return the score of every vertex, indexed by vertex.
+        (0..self.len()).map(|v| self.score(v)).collect()
+    }
+
+    pub fn score_sequence(&self) -> Vec<usize> {
+        //! Return the scores of self in nondecreasing order.
+        let mut s = self.scores();
+        s.sort_unstable();
+        s
+    }
+
+    pub fn is_transitive(&self) -> bool {
+        //! Decide whether self is transitive, which holds exactly when all scores differ.
+        self.score_sequence().iter().enumerate().all(|(i, &s)| i == s)
+    }
+
+    pub fn is_strongly_connected(&self) -> bool {
+        //! Decide whether every vertex reaches every other one.
+        let reaches_all = |dists: Vec<i32>| dists.iter().all(|&d| d >= 0);
+        self.is_empty() || (reaches_all(utils::bfs(&self.graph, 0, &mut |_, _, _| {}).0) &&
+            reaches_all(utils::bfs(&views::transpose(&self.graph), 0, &mut |_, _, _| {}).0))
+    }
+
+    pub fn kings(&self) -> Vec<usize> {
+        //! Return the vertices that reach every other vertex by a path of at most two arcs. Every
+        //! tournament has one, and a vertex of maximum score is always a king.
+        (0..self.len()).filter(|&v| {
+            let mut reach = self.graph.out_neighbors(v).into_owned();
+            reach.set(v, true);
+            for w in self.graph.out_neighbor_iter(v) {
+                reach.union(&self.graph.out_neighbors(w));
+            }
+            reach.all()
+        }).collect()
+    }
+
+    pub fn hamiltonian_path(&self) -> Vec<usize> {
+        //! Return an ordering of all vertices where each beats the next. Every tournament has
+        //! one; each vertex is inserted before the first vertex of the path it beats.
+        let mut path: Vec<usize> = Vec::with_capacity(self.len());
+        for v in 0..self.len() {
+            let pos = path.iter().position(|&w| self.graph.has_edge(v, w)).unwrap_or(path.len());
+            path.insert(pos, v);
+        }
+        path
+    }
+
+    pub fn hamiltonian_cycle(&self) -> Option<Vec<usize>> {
+        //! Return an ordering of all vertices where each beats the next and the last beats the
+        //! first, or None if self is not strongly connected. A single vertex is a cycle.
+        let n = self.len();
+        if n == 0 || !self.is_strongly_connected() {
+            return None;
+        }
+        if n == 1 {
+            return Some(vec![0]);
+        }
+        // start from a 3-cycle 0 -> b -> c -> 0, which exists since self is strong
+        let ins = self.graph.in_neighbors(0);
+        let mut cycle = self.graph.out_neighbor_iter(0).find_map(|b| {
+            kernels::first_and(&self.graph.out_neighbors(b), &ins).map(|c| vec![0, b, c])
+        }).unwrap();
+        let mut on = BitVec::from_elem(n, false);
+        for &v in cycle.iter() {
+            on.set(v, true);
+        }
+        while cycle.len() < n {
+            let k = cycle.len();
+            // a vertex with arcs both to and from the cycle sits between some c -> v -> c'
+            let mixed = (0..n).filter(|&v| !on[v]).find_map(|v| {
+                (0..k).find(|&i| self.graph.has_edge(cycle[i], v) &&
+                            self.graph.has_edge(v, cycle[(i + 1) % k])).map(|i| (i, v))
+            });
+            if let Some((i, v)) = mixed {
+                cycle.insert(i + 1, v);
+                on.set(v, true);
+                continue;
+            }
+            // otherwise each outside vertex is beaten by the whole cycle (a) or beats all of it
+            // (b), and strong connectivity gives an arc a -> b; then c0 -> a -> b -> c2 skips c1
+            let beaten = |v: usize| self.graph.has_edge(cycle[0], v);
+            let (a, b) = (0..n).filter(|&a| !on[a] && beaten(a)).find_map(|a| {
+                (0..n).find(|&b| !on[b] && !beaten(b) && self.graph.has_edge(a, b))
+                    .map(|b| (a, b))
+            }).unwrap();
+            on.set(cycle[1], false);
+            on.set(a, true);
+            on.set(b, true);
+            cycle.splice(1..2, vec![a, b]);
+        }
+        Some(cycle)
+    }
+}
+
+impl GraphRef for Tournament {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        true
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(from, to)
+    }
+}
@@ -1,37 +1,38 @@
-use BitGraph;
-use std::collections::{BitVec, BitSet, VecDeque};
+use GraphRef;
+use std::collections::VecDeque;
+use bitset::{BitVec, BitSet};
+use bitset::kernels;
 
 pub fn sum(vec: &BitVec) -> usize {
     //! Return number of true elements in given vector.
-    vec.iter().filter(|v| *v).count()
+    kernels::count_ones(vec)
 }
 
 pub fn dot(a: &BitVec, b: &BitVec) -> usize {
     //! Return dot product of a and b.
-    let mut comp = a.clone();
-    comp.intersect(b);
-    sum(&comp)
+    kernels::and_count(a, b)
 }
 
 pub fn bfs<G,F>(g: &G, start: usize, visitor: &mut F) -> (Vec<i32>, Vec<usize>)
-    where G:BitGraph, F:FnMut(usize, &[i32], &[usize]) {
+    where G:GraphRef, F:FnMut(usize, &[i32], &[usize]) {
     //! Perform breadth-first search on graph from given start.
     //! Call visitor at each vertex visited in BFS order with parents and distances.
     //! Return (mapping of id->depth, -1 for unreached vertices, id->parent id)
     let mut dists = vec![-1; g.len()];
     let mut q = VecDeque::with_capacity(g.len());
-    let mut visited = BitSet::with_capacity(g.len());
+    let mut visited = BitVec::from_elem(g.len(), false);
     let mut parents = vec![0; g.len()];
     q.push_back(start);
-    visited.insert(start);
+    visited.set(start, true);
     dists[start] = 0;
-    while !q.is_empty() {
-        let v = q.pop_front().unwrap();
-        for n in BitSet::from_bit_vec(g.out_neighbors(v).clone())
-                    .iter().filter(|&v| visited.insert(v)) {
-            dists[n] = dists[v] + 1;
-            parents[n] = v;
-            q.push_back(n);
+    while let Some(v) = q.pop_front() {
+        for n in g.out_neighbor_iter(v) {
+            if !visited[n] {
+                visited.set(n, true);
+                dists[n] = dists[v] + 1;
+                parents[n] = v;
+                q.push_back(n);
+            }
         }
         visitor(v, &dists, &parents);
     }
@@ -39,56 +40,63 @@ pub fn bfs<G,F>(g: &G, start: usize, visitor: &mut F) -> (Vec<i32>, Vec<usize>)
 }
 
 pub fn dfs<G,F>(g: &G, start: usize, visitor: &mut F) -> Vec<i32>
-    where G:BitGraph, F:FnMut(usize) {
+    where G:GraphRef, F:FnMut(usize) {
     //! Perform depth-first search on graph from given start.
     //! Call optional visitor at each vertex visited in DFS order.
     //! Return mapping of id->visit time, -1 for unreached vertices.
     let mut order = vec![-1; g.len()];
     let mut stack = vec![start];
     let mut iter = 0;
-    let mut visited = BitSet::with_capacity(g.len());
-    visited.insert(start);
-    while !stack.is_empty() {
-        let v = stack.pop().unwrap();
+    let mut visited = BitVec::from_elem(g.len(), false);
+    visited.set(start, true);
+    while let Some(v) = stack.pop() {
         order[v] = iter;
-        stack.append(&mut BitSet::from_bit_vec(g.out_neighbors(v).clone())
-                        .iter().filter(|&v| visited.insert(v)).collect());
+        for n in g.out_neighbor_iter(v) {
+            if !visited[n] {
+                visited.set(n, true);
+                stack.push(n);
+            }
+        }
         iter += 1;
         visitor(v);
     }
     order
 }
 
-pub fn is_independent<G: BitGraph>(g: &G, s: &BitSet) -> bool {
+pub fn is_independent<G: GraphRef>(g: &G, s: &BitSet) -> bool {
     //! Decide whether given s is an independent set in g.
-    s.iter().all(|u| s.iter().all(|v| !g.out_neighbors(u)[v]))
+    s.iter().all(|u| g.out_neighbor_iter(u).all(|w| !s.contains(w)))
 }
 
-pub fn is_clique<G: BitGraph>(g: &G, v: &BitVec) -> bool {
+pub fn is_clique<G: GraphRef>(g: &G, v: &BitVec) -> bool {
     //! Decide whether v forms a clique in g.
-    let k = sum(v) - 1;
-    // filter for elements in v, check that their intersection is k
-    (0..g.len()).filter(|i| v[*i]).all(|i| dot(v, g.out_neighbors(i)) == k)
+    let k = sum(v);
+    // each element of v must be adjacent to the other k-1
+    kernels::ones(v).all(|i| g.out_neighbor_iter(i).filter(|&w| v[w]).count() + 1 == k)
 }
 
-fn assign_color(neighbors: &BitVec, coloring: &Vec<BitSet>) -> usize {
+fn assign_color<I: Iterator<Item = usize>>(neighbors: I, colors: &[usize]) -> usize {
     //! Pick a color that does not conflict with any neighbors.
-    //! Color can be in range [0..len(colors)] (note the inclusive range).
-    coloring.iter().enumerate().find(|&(_,c)| {
-        let mut color_vec = c.clone().into_bit_vec();
-        color_vec.intersect(neighbors);
-        sum(&color_vec) == 0
-    }).map(|(i,_)| i).unwrap_or(coloring.len())
+    //! Color can be in range [0..number of colors] (note the inclusive range).
+    let mut taken: Vec<usize> = neighbors.map(|w| colors[w]).filter(|&c| c != usize::MAX)
+        .collect();
+    taken.sort_unstable();
+    taken.dedup();
+    // the first gap in the sorted colors, or the next color if there is none
+    taken.iter().enumerate().position(|(i, &c)| i != c).unwrap_or(taken.len())
 }
 
-pub fn greedy_color<G:BitGraph>(g: &G, order: &Vec<usize>) -> Vec<BitSet> {
+pub fn greedy_color<G:GraphRef>(g: &G, order: &[usize]) -> Vec<BitSet> {
     //! Perform a greedy coloring of g, visiting vertices in the specified order.
     //! Return a vector of the color classes in g.
     //! This will use at most max degree colors.
     let mut coloring = vec![BitSet::with_capacity(g.len())];
+    // colors[v] is the color of v, usize::MAX until it has one
+    let mut colors = vec![usize::MAX; g.len()];
     // assign each vertex in the order the first available color
     for &v in order.iter() {
-        let c = assign_color(g.out_neighbors(v), &coloring);
+        let c = assign_color(g.out_neighbor_iter(v), &colors);
+        colors[v] = c;
         if c < coloring.len() {
             coloring[c].insert(v);
         } else {
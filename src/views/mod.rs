@@ -0,0 +1,186 @@
+//! Borrowed views of a graph that answer queries without copying its matrix. Each view
+//! implements GraphRef, so views nest and every function in utils runs on them directly.
+
+use std::borrow::Cow;
+use bitset::BitVec;
+use {GraphRef, LoopPolicy, Neighbors};
+
+/// Subgraph induced by a set of vertices, with the same semantics as BitGraph::induce: the size
+/// is unchanged and vertices outside the set are disconnected.
+pub struct Induced<'a, G: 'a + GraphRef + ?Sized> {
+    graph: &'a G,
+    vertices: &'a BitVec
+}
+
+/// Complement of a graph, as BitGraph::complement builds it: the diagonal is negated along with
+/// the rest if the graph allows loops, and left empty otherwise.
+pub struct Complement<'a, G: 'a + GraphRef + ?Sized> {
+    graph: &'a G
+}
+
+/// Graph with every edge reversed. For an undirected graph this is the graph itself.
+pub struct Transpose<'a, G: 'a + GraphRef + ?Sized> {
+    graph: &'a G
+}
+
+pub fn induced<'a, G: GraphRef + ?Sized>(graph: &'a G, vertices: &'a BitVec) -> Induced<'a, G> {
+    //! View the subgraph of graph induced by vertices, |vertices| = |V|.
+    assert_eq!(vertices.len(), graph.len(), "vertex set must cover the graph");
+    Induced { graph, vertices }
+}
+
+pub fn complement<G: GraphRef + ?Sized>(graph: &G) -> Complement<'_, G> {
+    //! View the complement of graph.
+    Complement { graph }
+}
+
+pub fn transpose<G: GraphRef + ?Sized>(graph: &G) -> Transpose<'_, G> {
+    //! View graph with its edges reversed.
+    Transpose { graph }
+}
+
+impl<'a, G: GraphRef + ?Sized> Induced<'a, G> {
+    fn restrict(&self, id: usize, row: Cow<BitVec>) -> Cow<'_, BitVec> {
+        let mut r = row.into_owned();
+        if self.vertices[id] {
+            r.intersect(self.vertices);
+        } else {
+            r.clear();
+        }
+        Cow::Owned(r)
+    }
+
+    fn mask(&self, id: usize, row: Neighbors<'a>) -> Neighbors<'a> {
+        // a vertex outside the set loses all its edges, not just those leaving the set
+        if self.vertices[id] {
+            row.masked(self.vertices)
+        } else {
+            Neighbors::list(&[])
+        }
+    }
+}
+
+impl<'a, G: GraphRef + ?Sized> GraphRef for Induced<'a, G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.restrict(id, self.graph.in_neighbors(id))
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.restrict(id, self.graph.out_neighbors(id))
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.mask(id, self.graph.out_neighbor_iter(id))
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.mask(id, self.graph.in_neighbor_iter(id))
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.out_neighbor_iter(id).count()
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.in_neighbor_iter(id).count()
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.vertices[from] && self.vertices[to] && self.graph.has_edge(from, to)
+    }
+}
+
+impl<'a, G: GraphRef + ?Sized> Complement<'a, G> {
+    fn loops(&self) -> bool {
+        self.graph.loop_policy() == LoopPolicy::Allow
+    }
+
+    fn flip(&self, id: usize, row: Cow<BitVec>) -> Cow<'static, BitVec> {
+        // negate the row, and zero the diagonal unless loops are kept
+        let mut r = row.into_owned();
+        r.negate();
+        if !self.loops() {
+            r.set(id, false);
+        }
+        Cow::Owned(r)
+    }
+
+    fn skip(&self, id: usize) -> Option<usize> {
+        if self.loops() { None } else { Some(id) }
+    }
+
+    fn missing(&self, id: usize, degree: usize) -> usize {
+        // every vertex but the neighbors, less id itself when it is skipped and not among them
+        let skipped = !self.loops() && !self.graph.has_edge(id, id);
+        self.len() - degree - skipped as usize
+    }
+}
+
+impl<'a, G: GraphRef + ?Sized> GraphRef for Complement<'a, G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.flip(id, self.graph.in_neighbors(id))
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.flip(id, self.graph.out_neighbors(id))
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id).missing(self.len(), self.skip(id))
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id).missing(self.len(), self.skip(id))
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.missing(id, self.graph.out_degree(id))
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.missing(id, self.graph.in_degree(id))
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        (from != to || self.loops()) && !self.graph.has_edge(from, to)
+    }
+}
+
+impl<'a, G: GraphRef + ?Sized> GraphRef for Transpose<'a, G> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.graph.in_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.graph.out_degree(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(to, from)
+    }
+}
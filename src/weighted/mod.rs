@@ -0,0 +1,288 @@
+use std::borrow::Cow;
+use std::mem;
+use bitset::BitVec;
+use {GraphRef, GraphMut, BitGraph, GraphError, LoopPolicy, Removal, Neighbors};
+use {NodeAttrs, EdgeAttrs};
+
+/// Graph whose edges carry weights of type W. Topology lives in the wrapped BitGraph, so queries
+/// cost the same as on G, while weights are kept in sorted per-row lists holding only existing
+/// edges. An undirected edge {u, v} is stored once, in the row of min(u, v).
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedGraph<G: BitGraph, W> {
+    /// Topology of the graph.
+    graph: G,
+    /// weights[u] lists (v, w) sorted by v for every stored edge (u, v).
+    weights: Vec<Vec<(usize, W)>>
+}
+
+pub fn new<G: BitGraph, W: Clone>(graph: G, default: W) -> WeightedGraph<G, W> {
+    //! Wrap graph, giving each of its edges the weight default.
+    let edges = graph.edges().map(|(u, v)| (u, v, default.clone()));
+    let weights = store(&graph, edges);
+    WeightedGraph { graph, weights }
+}
+
+fn store<G, W, I>(graph: &G, entries: I) -> Vec<Vec<(usize, W)>>
+    where G: GraphRef, I: IntoIterator<Item = (usize, usize, W)>
+{
+    // lay out weights for the edges of graph, dropping entries that are not edges
+    let mut weights: Vec<Vec<(usize, W)>> = (0..graph.len()).map(|_| Vec::new()).collect();
+    for (u, v, w) in entries {
+        if graph.has_edge(u, v) {
+            let (u, v) = key(graph.is_directed(), u, v);
+            weights[u].push((v, w));
+        }
+    }
+    for row in weights.iter_mut() {
+        row.sort_by_key(|&(v, _)| v);
+    }
+    weights
+}
+
+fn key(directed: bool, u: usize, v: usize) -> (usize, usize) {
+    if directed || u <= v { (u, v) } else { (v, u) }
+}
+
+impl<G: BitGraph, W> WeightedGraph<G, W> {
+    fn slot(&self, u: usize, v: usize) -> (usize, Result<usize, usize>) {
+        // row holding the edge (u, v) and its position, or where it would be inserted
+        let (u, v) = key(self.graph.is_directed(), u, v);
+        (u, self.weights[u].binary_search_by_key(&v, |&(x, _)| x))
+    }
+
+    fn put(&mut self, u: usize, v: usize, w: W) {
+        let (_, col) = key(self.graph.is_directed(), u, v);
+        match self.slot(u, v) {
+            (row, Ok(pos)) => self.weights[row][pos].1 = w,
+            (row, Err(pos)) => self.weights[row].insert(pos, (col, w))
+        }
+    }
+
+    fn take(&mut self, u: usize, v: usize) -> Option<W> {
+        match self.slot(u, v) {
+            (row, Ok(pos)) => Some(self.weights[row].remove(pos).1),
+            (_, Err(_)) => None
+        }
+    }
+
+    fn take_incident(&mut self, v: usize) -> Vec<(usize, usize, W)> {
+        // remove and return every stored edge with v as an endpoint
+        let mut taken = Vec::new();
+        for (u, row) in self.weights.iter_mut().enumerate() {
+            let old = mem::take(row);
+            for (x, w) in old {
+                if u == v || x == v {
+                    taken.push((u, x, w));
+                } else {
+                    row.push((x, w));
+                }
+            }
+        }
+        taken
+    }
+
+    pub fn graph(&self) -> &G {
+        //! Return the topology of self.
+        &self.graph
+    }
+
+    pub fn into_inner(self) -> G {
+        //! Drop the weights and return the topology of self.
+        self.graph
+    }
+
+    pub fn weight(&self, u: usize, v: usize) -> Option<&W> {
+        //! Return the weight of edge (u, v), or None if there is no such edge.
+        match self.slot(u, v) {
+            (row, Ok(pos)) => Some(&self.weights[row][pos].1),
+            (_, Err(_)) => None
+        }
+    }
+
+    pub fn set_weight(&mut self, u: usize, v: usize, w: W) -> Result<(), GraphError> {
+        //! Change the weight of edge (u, v), or fail if it is not an edge of self.
+        self.graph.check_vertex(u)?;
+        self.graph.check_vertex(v)?;
+        if !self.graph.has_edge(u, v) {
+            return Err(GraphError::MissingEdge(u, v));
+        }
+        self.put(u, v, w);
+        Ok(())
+    }
+
+    pub fn add_weighted_edge(&mut self, u: usize, v: usize, w: W) {
+        //! Add edge (u, v) with weight w, replacing the weight if the edge exists. Loops the
+        //! topology does not keep are dropped along with their weight.
+        self.graph.add_edge(u, v);
+        if self.graph.has_edge(u, v) {
+            self.put(u, v, w);
+        }
+    }
+
+    pub fn remove_weighted_edge(&mut self, u: usize, v: usize) -> Option<W> {
+        //! Remove edge (u, v) and return its weight, or None if there was no such edge.
+        self.graph.remove_edge(u, v);
+        self.take(u, v)
+    }
+
+    pub fn weighted_edges(&self) -> impl Iterator<Item = (usize, usize, &W)> + '_ {
+        //! Iterate over (u, v, weight) for every edge, in order of u then v. Undirected edges are
+        //! yielded once, with u <= v.
+        self.weights.iter().enumerate()
+            .flat_map(|(u, row)| row.iter().map(move |&(v, ref w)| (u, v, w)))
+    }
+
+    pub fn contract_with<F>(&mut self, e: (usize, usize), mut combine: F)
+        where F: FnMut(W, W) -> W
+    {
+        //! Contract edge e = (u->v) as GraphMut::contract does. An edge of v that lands on an
+        //! existing edge of u is merged into it with combine(weight at u, weight at v).
+        let (u, v) = e;
+        assert!(self.graph.has_edge(u, v));
+        let moved = self.take_incident(v);
+        self.graph.contract(e);
+        let merge = |x: usize| if x == v { u } else { x };
+        for (a, b, w) in moved {
+            let (a, b) = (merge(a), merge(b));
+            if !self.graph.has_edge(a, b) {
+                continue;
+            }
+            let w = match self.take(a, b) {
+                Some(old) => combine(old, w),
+                None => w
+            };
+            self.put(a, b, w);
+        }
+    }
+}
+
+impl<G: BitGraph, W> GraphRef for WeightedGraph<G, W> {
+    fn len(&self) -> usize {
+        self.graph.len()
+    }
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+    fn loop_policy(&self) -> LoopPolicy {
+        self.graph.loop_policy()
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.in_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.graph.out_neighbors(id)
+    }
+    fn out_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.out_neighbor_iter(id)
+    }
+    fn in_neighbor_iter(&self, id: usize) -> Neighbors<'_> {
+        self.graph.in_neighbor_iter(id)
+    }
+    fn out_degree(&self, id: usize) -> usize {
+        self.graph.out_degree(id)
+    }
+    fn in_degree(&self, id: usize) -> usize {
+        self.graph.in_degree(id)
+    }
+    fn neighbors(&self, id: usize) -> BitVec {
+        self.graph.neighbors(id)
+    }
+    fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.graph.has_edge(from, to)
+    }
+}
+
+impl<G: BitGraph, W: Clone + Default> GraphMut for WeightedGraph<G, W> {
+    fn add_edge(&mut self, fr: usize, to: usize) {
+        // new edges get the default weight, existing ones keep theirs
+        if self.weight(fr, to).is_none() {
+            self.add_weighted_edge(fr, to, W::default());
+        }
+    }
+    fn remove_edge(&mut self, fr: usize, to: usize) {
+        self.remove_weighted_edge(fr, to);
+    }
+    fn add_vertex(&mut self) -> usize {
+        self.weights.push(Vec::new());
+        self.graph.add_vertex()
+    }
+    fn remove_vertex(&mut self, v: usize, mode: Removal) -> Option<usize> {
+        self.take_incident(v);
+        let moved = self.graph.remove_vertex(v, mode);
+        if let Some(last) = moved {
+            let old = mem::take(&mut self.weights);
+            let relabel = |x: usize| if x == last { v } else { x };
+            let entries = old.into_iter().enumerate()
+                .flat_map(|(a, row)| row.into_iter().map(move |(b, w)| (a, b, w)))
+                .map(|(a, b, w)| (relabel(a), relabel(b), w));
+            self.weights = store(&self.graph, entries);
+        } else if mode == Removal::SwapRemove {
+            self.weights.pop();
+        }
+        moved
+    }
+    fn induce(&mut self, vertices: &BitVec) {
+        self.graph.induce(vertices);
+        for (u, row) in self.weights.iter_mut().enumerate() {
+            row.retain(|&(v, _)| vertices[u] && vertices[v]);
+        }
+    }
+    fn contract(&mut self, e: (usize, usize)) {
+        // keep the weight of the edge at u when two edges merge
+        self.contract_with(e, |a, _| a);
+    }
+    fn try_add_edge(&mut self, fr: usize, to: usize) -> Result<(), GraphError> {
+        // weights are looked up by row, so the ends are checked first
+        self.graph.check_vertex(fr)?;
+        self.graph.check_vertex(to)?;
+        if self.weight(fr, to).is_none() {
+            self.graph.try_add_edge(fr, to)?;
+            if self.graph.has_edge(fr, to) {
+                self.put(fr, to, W::default());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<G: BitGraph, W: Clone + Default> BitGraph for WeightedGraph<G, W> {
+    fn verify(&self) -> bool {
+        //! Make sure the topology is valid and every edge has exactly one weight.
+        let directed = self.graph.is_directed();
+        self.graph.verify() && self.weights.len() == self.graph.len() &&
+            self.weights.iter().enumerate().all(|(u, row)| {
+                row.windows(2).all(|p| p[0].0 < p[1].0) &&
+                    row.iter().all(|&(v, _)| (directed || u <= v) && self.graph.has_edge(u, v))
+            }) &&
+            self.weights.iter().map(Vec::len).sum::<usize>() == self.graph.edge_count()
+    }
+    fn complement(&self) -> Self {
+        // none of the new edges existed before, so they all get the default weight
+        new(self.graph.complement(), W::default())
+    }
+    fn compressed(&self) -> (Self, Vec<usize>) {
+        let (graph, map) = self.graph.compressed();
+        let mut inverse = vec![0; self.len()];
+        for (i, &v) in map.iter().enumerate() {
+            inverse[v] = i;
+        }
+        let entries = self.weighted_edges().map(|(u, v, w)| (inverse[u], inverse[v], w.clone()));
+        let weights = store(&graph, entries);
+        (WeightedGraph { graph, weights }, map)
+    }
+    fn reordered(&self, order: &[usize]) -> Self {
+        let graph = self.graph.reordered(order);
+        let mut inverse = vec![0; self.len()];
+        for (i, &v) in order.iter().enumerate() {
+            inverse[v] = i;
+        }
+        let entries = self.weighted_edges().map(|(u, v, w)| (inverse[u], inverse[v], w.clone()));
+        let weights = store(&graph, entries);
+        WeightedGraph { graph, weights }
+    }
+    fn serialize_dot(&self, node_attrs: Option<&NodeAttrs>, edge_attrs: Option<&EdgeAttrs>)
+        -> String
+    {
+        self.graph.serialize_dot(node_attrs, edge_attrs)
+    }
+}
@@ -0,0 +1,56 @@
+use bitgraphs::{BitGraph, GraphMut, GraphError, Removal};
+use bitgraphs::{attributed, graph};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_attributes() {
+    let mut g = attributed::new(graph::new(4));
+    g.add_edge(0, 1);
+    g.add_edge(1, 2);
+    g.set_graph_attr("rankdir", "LR");
+    g.set_node_default("shape", "box");
+    g.set_edge_default("color", "gray");
+    g.set_node_attr(2, "shape", "circle").unwrap();
+    g.set_edge_attr(2, 1, "color", "red").unwrap();
+    assert!(g.set_edge_attr(0, 2, "color", "red") == Err(GraphError::MissingEdge(0, 2)));
+    assert!(g.node_attr(2, "shape") == Some("circle") && g.node_attr(0, "shape") == Some("box"));
+    assert!(g.edge_attr(1, 2, "color") == Some("red"));
+    assert!(g.edge_attr(0, 1, "color") == Some("gray"));
+    assert!(g.edge_attr(0, 3, "color").is_none() && g.graph_attr("rankdir") == Some("LR"));
+
+    let dot = g.serialize_dot(None, None);
+    assert!(dot.contains("graph [rankdir=\"LR\"]") && dot.contains("edge [color=\"gray\"]"));
+    assert!(dot.contains("node [fontname=\"sans-serif\",fontsize=\"12\",shape=\"box\"]"));
+    assert!(dot.contains("2 [id=2,shape=\"circle\"]"));
+    assert!(dot.contains("1 -- 2 [id=\"1,2\",color=\"red\"]"));
+}
+
+#[test]
+fn test_attributes_follow() {
+    let mut g = attributed::new(graph::new(4));
+    g.add_edge(1, 3);
+    g.add_edge(2, 3);
+    g.set_node_attr(3, "label", "c").unwrap();
+    g.set_edge_attr(1, 3, "w", "1").unwrap();
+    g.set_edge_attr(2, 3, "w", "2").unwrap();
+
+    let (c, map) = g.compressed();
+    assert_eq!(map, vec![1, 2, 3]);
+    assert!(c.node_attr(2, "label") == Some("c") && c.edge_attr(0, 2, "w") == Some("1"));
+    let r = g.reordered(&[3, 2, 1, 0]);
+    assert!(r.node_attr(0, "label") == Some("c") && r.edge_attr(1, 0, "w") == Some("2"));
+    assert!(r.verify());
+
+    let mut i = g.clone();
+    i.induce(&BitVec::from_fn(4, |v| v != 1));
+    assert!(i.edge_attr(1, 3, "w").is_none() && i.edge_attrs().len() == 1 && i.verify());
+
+    // contracting 3 into 2 moves 1-3 onto 1-2
+    let mut k = g.clone();
+    k.contract((2, 3));
+    assert!(k.edge_attr(1, 2, "w") == Some("1") && k.edge_attrs().len() == 1 && k.verify());
+
+    assert!(g.remove_vertex(0, Removal::SwapRemove) == Some(3));
+    assert!(g.node_attr(0, "label") == Some("c") && g.edge_attr(2, 0, "w") == Some("2"));
+    assert!(g.verify());
+}
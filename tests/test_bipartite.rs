@@ -0,0 +1,39 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError};
+use bitgraphs::{bipartite, graph, utils};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_bipartite() {
+    // left {0, 1, 2}, right {3, 4}: 0-3, 1-3, 2-4
+    let mut b = bipartite::new(3, 2);
+    b.add_edge(0, 3);
+    b.add_edge(3, 1);
+    b.add_edge(2, 4);
+    assert!(b.try_add_edge(0, 1) == Err(GraphError::SamePart(0, 1)));
+    assert!(b.has_edge(3, 0) && !b.has_edge(0, 4) && !b.has_edge(0, 1));
+    assert!(*b.out_neighbors(3) == BitVec::from_fn(5, |i| i < 2));
+    let (dist, _) = utils::bfs(&b, 0, &mut |_, _, _| {});
+    assert_eq!(dist, vec![0, 2, -1, 1, -1]);
+
+    let g = b.to_graph();
+    assert!(g.verify() && (0..5).all(|v| g.out_neighbors(v) == b.out_neighbors(v)));
+    let l = b.left_projection();
+    assert!(l.has_edge(0, 1) && !l.has_edge(1, 2) && l.len() == 3);
+    let r = b.right_projection();
+    assert!(r.len() == 2 && !r.has_edge(0, 1));
+    assert!(bipartite::complete(2, 2).right_projection().has_edge(0, 1));
+}
+
+#[test]
+fn test_from_graph() {
+    // the path 0-1-2-3 split into even and odd vertices
+    let mut g = graph::new(4);
+    g.add_edges(1, &[0, 2]);
+    g.add_edge(2, 3);
+    let (b, map) = bipartite::from_graph(&g, &BitVec::from_fn(4, |i| i % 2 == 0)).unwrap();
+    assert_eq!(map, vec![0, 2, 1, 3]);
+    assert!(b.left_len() == 2 && b.has_edge(0, 2) && b.has_edge(1, 2) && b.has_edge(1, 3));
+    assert!(!b.has_edge(0, 3));
+    let odd = bipartite::from_graph(&g, &BitVec::from_fn(4, |i| i < 2));
+    assert!(odd == Err(GraphError::SamePart(0, 1)));
+}
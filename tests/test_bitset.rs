@@ -0,0 +1,30 @@
+use bitgraphs::bitset::{BitVec, BitSet};
+
+#[test]
+fn test_bitvec() {
+    let mut v = BitVec::from_elem(70, false);
+    v.set(3, true);
+    v.set(69, true);
+    assert!(v[3] && v[69] && !v[4]);
+    v.negate();
+    assert!(v.iter().filter(|&b| b).count() == 68);
+    let mut w = BitVec::from_fn(70, |i| i % 2 == 0);
+    w.intersect(&v);
+    assert!(w[0] && !w[3] && w[68]);
+    v.set_all();
+    assert!(v.all());
+    v.truncate(65);
+    v.push(false);
+    assert!(v.len() == 66 && !v[65] && v[64]);
+}
+
+#[test]
+fn test_bitset() {
+    let mut s = BitSet::with_capacity(4);
+    assert!(s.insert(2));
+    assert!(!s.insert(2));
+    assert!(s.insert(100));
+    assert!(s.iter().collect::<Vec<_>>() == vec![2, 100]);
+    assert!(s.remove(2));
+    assert!(s.len() == 1);
+}
@@ -1,4 +1,4 @@
-use bitgraphs::BitGraph;
+use bitgraphs::{GraphRef, GraphMut};
 use bitgraphs::graph;
 use bitgraphs::digraph;
 
@@ -8,10 +8,9 @@ use bitgraphs::digraph;
 fn test_read_csv() {
     let path = "tests/data/petersen.csv";
     if let Ok(file) = File::open(path) {
-        if let Some(g) = graph::read_csv(&mut io::BufReader::new(file)) {
-            println!("{}", g.serialize_dot(None, None));
-        } else {
-            panic!("Could not read csv to graph.");
+        match graph::read_csv(&mut io::BufReader::new(file)) {
+            Ok(g) => println!("{}", g.serialize_dot(None, None)),
+            Err(e) => panic!("Could not read csv to graph: {}", e)
         }
     } else {
         panic!("Could not open file.");
@@ -22,10 +21,9 @@ fn test_read_csv() {
 fn test_read_csv_digraph() {
     let path = "tests/data/tourney.csv";
     if let Ok(file) = File::open(path) {
-        if let Some(g) = digraph::read_csv(&mut io::BufReader::new(file)) {
-            println!("{}", g.serialize_dot(None, None));
-        } else {
-            panic!("Could not read csv to graph.");
+        match digraph::read_csv(&mut io::BufReader::new(file)) {
+            Ok(g) => println!("{}", g.serialize_dot(None, None)),
+            Err(e) => panic!("Could not read csv to graph: {}", e)
         }
     } else {
         panic!("Could not open file.");
@@ -0,0 +1,69 @@
+use bitgraphs::bitset::BitVec;
+use bitgraphs::{GraphRef, GraphMut, LoopPolicy};
+use bitgraphs::{graph, digraph, hybrid, packed, bipartite, views};
+
+#[test]
+fn test_iterators() {
+    let mut g = graph::with_loops(4, LoopPolicy::Allow);
+    g.add_edges(0, &[1, 2]);
+    g.add_edge(3, 3);
+    assert_eq!(g.out_neighbor_iter(0).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(g.edges().collect::<Vec<_>>(), vec![(0, 1), (0, 2), (3, 3)]);
+    assert!(g.edge_count() == 3 && g.degree(0) == 2 && g.degree(3) == 1);
+    assert_eq!(g.degree_sequence(), vec![2, 1, 1, 1]);
+
+    let mut d = digraph::new(3);
+    d.add_edges(0, &[1, 2]);
+    d.add_edge(2, 1);
+    assert_eq!(d.in_neighbor_iter(1).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(d.edges().collect::<Vec<_>>(), vec![(0, 1), (0, 2), (2, 1)]);
+    assert!(d.edge_count() == 3 && d.in_degree(1) == 2 && d.out_degree(0) == 2);
+    assert_eq!(d.degree_sequence(), vec![2, 2, 2]);
+    let t = views::transpose(&d);
+    assert_eq!(t.edges().collect::<Vec<_>>(), vec![(1, 0), (1, 2), (2, 0)]);
+}
+
+#[test]
+fn test_wide_degrees() {
+    // rows spanning several words, with a hybrid graph counting sparse rows directly
+    let mut g = graph::new(200);
+    let mut h = hybrid::new(200);
+    for v in (1..200).filter(|v| v % 3 == 0) {
+        g.add_edge(0, v);
+        h.add_edge(0, v);
+    }
+    assert!(g.out_degree(0) == 66 && h.out_degree(0) == 66 && h.in_degree(198) == 1);
+    assert_eq!(g.out_neighbor_iter(0).last(), Some(198));
+    assert!(g.edge_count() == 66 && h.edge_count() == 66);
+}
+
+#[test]
+fn test_walks_match_rows() {
+    // every implementor's iterator and degrees agree with the rows it reports
+    let mut g = graph::new(70);
+    for v in 0..70 {
+        for w in (v + 1..70).filter(|w| (v * 7 + w * 3) % 5 == 0) {
+            g.add_edge(v, w);
+        }
+    }
+    let p = packed::PackedGraph::from_graph(&g);
+    let mut b = bipartite::new(30, 40);
+    for (v, w) in g.edges().filter(|&(v, w)| v < 30 && w >= 30) {
+        b.add_edge(v, w);
+    }
+    let some = BitVec::from_fn(70, |v| v % 4 != 1);
+    fn check<G: GraphRef>(g: &G) {
+        for v in 0..g.len() {
+            let row: Vec<usize> = (0..g.len()).filter(|&w| g.out_neighbors(v)[w]).collect();
+            assert_eq!(g.out_neighbor_iter(v).collect::<Vec<_>>(), row);
+            let col: Vec<usize> = (0..g.len()).filter(|&w| g.in_neighbors(v)[w]).collect();
+            assert_eq!(g.in_neighbor_iter(v).collect::<Vec<_>>(), col);
+            assert!(g.out_degree(v) == row.len() && g.in_degree(v) == col.len());
+        }
+    }
+    check(&p);
+    check(&b);
+    check(&views::induced(&g, &some));
+    check(&views::complement(&g));
+    check(&views::complement(&views::induced(&p, &some)));
+}
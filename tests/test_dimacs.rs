@@ -0,0 +1,46 @@
+use std::io;
+use bitgraphs::{GraphRef, GraphError};
+use bitgraphs::formats::{dimacs, DENSE_LIMIT};
+use bitgraphs::utils;
+
+#[test]
+fn test_dimacs_round_trip() {
+    let text = "c FILE: myciel3.col\nc\np edge 5 5\ne 1 2\ne 1 4\ne 2 3\ne 3 4\ne 4 5\ne 2 1\n";
+    let (g, comments) = dimacs::read_dimacs(&mut io::BufReader::new(text.as_bytes())).unwrap();
+    assert_eq!(comments, vec!["FILE: myciel3.col".to_string(), String::new()]);
+    assert!(g.len() == 5 && g.edge_count() == 5 && g.has_edge(4, 3));
+    let order: Vec<usize> = (0..g.len()).collect();
+    assert_eq!(utils::greedy_color(&g, &order).len(), 2);
+    let mut out = Vec::new();
+    dimacs::write_dimacs(&g, &comments, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), text.replace("e 2 1\n", ""));
+    let (d, _) = dimacs::read_dimacs_directed(&mut io::BufReader::new(text.as_bytes())).unwrap();
+    assert!(d.is_directed() && d.has_edge(3, 4) && !d.has_edge(4, 3) && d.edge_count() == 6);
+}
+
+#[test]
+fn test_dimacs_errors() {
+    let read = |text: &str| dimacs::read_dimacs(&mut io::BufReader::new(text.as_bytes()))
+        .map(|(g, _)| g);
+    let parse = |line, msg: &str| Err(GraphError::Parse { line, msg: msg.to_string() });
+    assert_eq!(read("c none\ne 1 2\n"), parse(2, "edge before problem line"));
+    assert_eq!(read("p edge 3 1\ne 1 4\n"), parse(2, "vertex 4 is outside 1 to 3"));
+    assert_eq!(read("p edge 3 1\ne 1\n"), parse(2, "expected 'e u v', got \"e 1\""));
+    assert_eq!(read("p edge 3 x\n"), parse(1, "\"x\": invalid digit found in string"));
+    assert_eq!(read("p edge 3 1\nn 1 2\n"), parse(2, "unknown line type \"n\""));
+    assert_eq!(read("c only\n"), parse(1, "no problem line"));
+}
+
+#[test]
+fn test_dimacs_huge_problem_line() {
+    // sizes are checked before the matrix is allocated
+    let read = |text: &str| dimacs::read_dimacs(&mut io::BufReader::new(text.as_bytes()));
+    assert_eq!(read("c big\np edge 300000 0\n").err(), Some(GraphError::Parse {
+        line: 2, msg: format!("300000 vertices, more than the {} of a dense graph", DENSE_LIMIT)
+    }));
+    let err = read("p edge 99999999999999999999 1\n").err().unwrap();
+    assert!(matches!(err, GraphError::Parse { line: 1, ref msg } if msg.contains("too large")));
+    assert_eq!(read("p edge 3 10\n").err(), Some(GraphError::Parse {
+        line: 1, msg: "10 edges, more than 3 vertices can have".to_string()
+    }));
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, LoopPolicy, NodeAttrs, EdgeAttrs};
+use bitgraphs::{attributed, digraph, graph, labeled};
+use bitgraphs::formats::dot;
+
+fn attrs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_dot_round_trip() {
+    let mut g = graph::new(6);
+    g.add_edges(0, &[1, 4]);
+    g.add_edge(3, 2);
+    let mut nodes = NodeAttrs::new();
+    nodes.insert(2, attrs(&[("color", "red")]));
+    let mut edges = EdgeAttrs::new();
+    edges.insert((2, 3), attrs(&[("label", "x y")]));
+    let parsed = dot::parse_dot(&g.serialize_dot(Some(&nodes), Some(&edges))).unwrap();
+    assert_eq!(parsed.graph, g);
+    assert_eq!(parsed.node_attrs[&2], attrs(&[("color", "red"), ("fontname", "sans-serif"),
+                                              ("fontsize", "12")]));
+    assert_eq!(parsed.edge_attrs, edges);
+    // quoted names that are not ids are numbered in order of appearance
+    let names = vec!["b \"q\"".to_string(), "a\\b".to_string(), "c".to_string()];
+    let mut d = digraph::new(3);
+    d.add_edge(2, 0);
+    let l = labeled::with_labels(d.clone(), names.clone()).unwrap();
+    let parsed = dot::parse_dot_directed(&l.serialize_dot(None, None)).unwrap();
+    assert_eq!((parsed.graph, parsed.names), (d, names));
+}
+
+#[test]
+fn test_dot_statements() {
+    let text = r#"
+        # generated
+        strict digraph G {
+            rankdir = LR; // left to right
+            node [shape=box]
+            a -> b -> {c d} [weight=2]
+            subgraph cluster_0 { edge [style=dashed]; e:n -> a }
+            /* a hand-drawn
+               part */
+            d [label=<<b>d</b>>, color="dark" + "red"]
+            c -> c
+        }"#;
+    let parsed = dot::parse_dot_directed(text).unwrap();
+    let g = &parsed.graph;
+    assert_eq!(parsed.names, vec!["a", "b", "c", "d", "e"]);
+    assert!(g.has_edge(0, 1) && g.has_edge(1, 2) && g.has_edge(1, 3) && g.has_edge(4, 0));
+    assert!(g.edge_count() == 4 && !g.has_edge(2, 2));
+    assert_eq!(parsed.graph_attrs, attrs(&[("rankdir", "LR")]));
+    assert_eq!(parsed.node_attrs[&3], attrs(&[("shape", "box"), ("label", "<b>d</b>"),
+                                              ("color", "darkred")]));
+    assert_eq!(parsed.edge_attrs[&(1, 3)], attrs(&[("weight", "2")]));
+    assert_eq!(parsed.edge_attrs[&(4, 0)], attrs(&[("style", "dashed")]));
+}
+
+#[test]
+fn test_dot_errors() {
+    let parse = |line, msg: &str| Err(GraphError::Parse { line, msg: msg.to_string() });
+    assert_eq!(dot::parse_dot("digraph { a -> b }").map(|d| d.graph),
+               parse(1, "expected graph, found \"digraph\""));
+    assert_eq!(dot::parse_dot("graph {\n a -> b }").map(|d| d.graph),
+               parse(2, "expected \"--\", found \"->\""));
+    assert_eq!(dot::parse_dot("graph {\n a -- b [color=]\n}").map(|d| d.graph),
+               parse(2, "expected an ID, found \"]\""));
+    assert_eq!(dot::parse_dot("graph { a \"b }").map(|d| d.graph),
+               parse(1, "unterminated string"));
+}
+
+#[test]
+fn test_dot_loops_and_ids() {
+    // loops survive under Allow, fail under Forbid and are dropped by default
+    let mut a = graph::with_loops(3, LoopPolicy::Allow);
+    a.add_edges(1, &[1, 2]);
+    let text = a.serialize_dot(None, None);
+    let parsed = dot::parse_dot_with(&text, LoopPolicy::Allow).unwrap();
+    assert_eq!(parsed.graph, a);
+    assert!(!dot::parse_dot(&text).unwrap().graph.has_edge(1, 1));
+    assert_eq!(dot::parse_dot_directed_with("digraph { 0 -> 0 }", LoopPolicy::Forbid)
+                   .map(|d| d.graph), Err(GraphError::SelfLoop(0)));
+    // ids with gaps are numbered like other names rather than leaving phantom vertices
+    let parsed = dot::parse_dot("graph { 5 -- 1000; 1000 -- 2 }").unwrap();
+    assert_eq!(parsed.names, vec!["5", "1000", "2"]);
+    assert!(parsed.graph.len() == 3 && parsed.graph.has_edge(0, 1) && parsed.graph.has_edge(2, 1));
+}
+
+#[test]
+fn test_dot_quoted_values() {
+    // quotes and backslashes in values and keys survive serialize_dot and back
+    let mut g = graph::new(2);
+    g.add_edge(0, 1);
+    let mut nodes = NodeAttrs::new();
+    nodes.insert(0, attrs(&[("label", "say \"hi\" \\ bye"), ("my key", "a\\nb")]));
+    let mut edges = EdgeAttrs::new();
+    edges.insert((0, 1), attrs(&[("label", "\"")]));
+    let parsed = dot::parse_dot(&g.serialize_dot(Some(&nodes), Some(&edges))).unwrap();
+    assert_eq!(parsed.node_attrs[&0]["label"], "say \"hi\" \\ bye");
+    assert_eq!(parsed.node_attrs[&0]["my key"], "a\\nb");
+    assert_eq!(parsed.edge_attrs, edges);
+    let mut a = attributed::new(digraph::new(2));
+    a.set_graph_attr("label", "x \"y\"");
+    a.set_edge_default("node", "\\");
+    let parsed = dot::parse_dot_directed(&a.serialize_dot(None, None)).unwrap();
+    assert_eq!(parsed.graph_attrs["label"], "x \"y\"");
+}
@@ -0,0 +1,101 @@
+use std::io;
+use bitgraphs::{GraphRef, GraphMut, GraphError, LoopPolicy};
+use bitgraphs::{graph, hybrid, weighted};
+use bitgraphs::formats::DENSE_LIMIT;
+use bitgraphs::formats::edgelist::{self, ListOptions};
+
+fn reader(text: &str) -> io::BufReader<&[u8]> {
+    io::BufReader::new(text.as_bytes())
+}
+
+#[test]
+fn test_edge_list() {
+    let text = "# road network\nfrom,to,km\n0,1,2.5\n1 2 4 # detour\n\n3\t1\t1.0\n";
+    let opts = ListOptions { header: true, weights: true, ..Default::default() };
+    let (g, edges) = edgelist::read_edge_list(&mut reader(text), &opts).unwrap();
+    assert!(g.len() == 4 && g.has_edge(1, 3) && g.edge_count() == 3);
+    assert_eq!(edges[1], (1, 2, Some(4.0)));
+    let opts = ListOptions { size: Some(6), header: true, ..Default::default() };
+    let (d, edges) = edgelist::read_edge_list_directed(&mut reader(text), &opts).unwrap();
+    assert!(d.len() == 6 && d.has_edge(3, 1) && !d.has_edge(1, 3) && edges[2].2.is_none());
+    let mut w = weighted::new(g, 0.0);
+    w.set_weight(2, 1, 4.5).unwrap();
+    let mut out = Vec::new();
+    edgelist::write_weighted_edge_list(&w, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "0 1 0\n1 2 4.5\n1 3 0\n");
+    let mut out = Vec::new();
+    edgelist::write_edge_list(w.graph(), &mut out).unwrap();
+    let (back, _) = edgelist::read_edge_list(&mut reader(&String::from_utf8(out).unwrap()),
+                                             &ListOptions::default()).unwrap();
+    assert_eq!(&back, w.graph());
+}
+
+#[test]
+fn test_edge_list_errors() {
+    let read = |text: &str, opts: ListOptions| {
+        edgelist::read_edge_list(&mut reader(text), &opts).map(|(g, _)| g)
+    };
+    let parse = |line, msg: &str| Err(GraphError::Parse { line, msg: msg.to_string() });
+    let weights = ListOptions { weights: true, ..Default::default() };
+    assert_eq!(read("0 1\n1 x\n", ListOptions::default()),
+               parse(2, "\"x\": invalid digit found in string"));
+    assert_eq!(read("0 1\n", weights), parse(1, "2 fields, expected at least 3"));
+    assert_eq!(read("0 5\n", ListOptions { size: Some(3), ..Default::default() }),
+               parse(1, "vertex 5 out of bounds for graph of size 3"));
+}
+
+#[test]
+fn test_adjacency_list() {
+    let mut g = graph::new(5);
+    g.add_edge(0, 3);
+    g.add_edge(3, 1);
+    let mut out = Vec::new();
+    edgelist::write_adjacency_list(&g, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(text, g.to_string());
+    let opts = ListOptions::default();
+    assert_eq!(edgelist::read_adjacency_list(&mut reader(&text), &opts).unwrap(), g);
+    let d = edgelist::read_adjacency_list_directed(&mut reader("# v out\n2 0,1\n0: 2\n"), &opts)
+        .unwrap();
+    assert!(d.len() == 3 && d.has_edge(2, 1) && !d.has_edge(1, 2) && d.edge_count() == 3);
+}
+
+#[test]
+fn test_large_sparse_lists() {
+    // a dense graph would need 11 GB here, so the dense readers refuse and hybrid ones read it
+    let text = "0 300000\n";
+    let opts = ListOptions::default();
+    assert_eq!(edgelist::read_edge_list(&mut reader(text), &opts).err(), Some(GraphError::Parse {
+        line: 1, msg: format!("300001 vertices, more than the {} of a dense graph", DENSE_LIMIT)
+    }));
+    let (h, edges) = edgelist::read_edge_list_into(&mut reader(text), &opts, 1 << 20,
+                                                   hybrid::new).unwrap();
+    assert!(h.len() == 300_001 && h.has_edge(300_000, 0) && h.edge_count() == 1);
+    let d = edgelist::read_adjacency_list_into(&mut reader("5: 1 7\n"), &opts, 8,
+                                               hybrid::new_directed).unwrap();
+    assert!(d.len() == 8 && d.has_edge(5, 7) && !d.has_edge(7, 5));
+    assert_eq!(edgelist::build(hybrid::new(300_001), &edges), Ok(h));
+    // the caller's limit holds before make is called, and ids at the top of usize are refused
+    let huge = edgelist::read_edge_list_into(&mut reader("0 1000000000000\n"), &opts, 1 << 20,
+                                             hybrid::new);
+    assert_eq!(huge.err(), Some(GraphError::Parse {
+        line: 1, msg: "1000000000001 vertices, more than the limit of 1048576".to_string()
+    }));
+    let top = format!("0 {}\n", usize::MAX);
+    let top = edgelist::read_edge_list_into(&mut reader(&top), &opts, usize::MAX, hybrid::new);
+    assert_eq!(top.err(), Some(GraphError::Parse {
+        line: 1, msg: format!("vertex {} too large", usize::MAX)
+    }));
+}
+
+#[test]
+fn test_list_loops() {
+    // a loop the target forbids is an error, not a panic
+    let opts = ListOptions::default();
+    let read = edgelist::read_edge_list_into(&mut reader("0 1\n2 2\n"), &opts, 10,
+                                             |n| graph::with_loops(n, LoopPolicy::Forbid));
+    assert_eq!(read.err(), Some(GraphError::SelfLoop(2)));
+    let read = edgelist::read_adjacency_list_into(&mut reader("1: 1\n"), &opts, 10,
+                                                  |n| graph::with_loops(n, LoopPolicy::Allow));
+    assert!(read.unwrap().has_edge(1, 1));
+}
@@ -0,0 +1,43 @@
+use std::io;
+use std::collections::HashMap;
+use bitgraphs::{BitGraph, GraphMut, GraphError, Removal};
+use bitgraphs::{graph, digraph};
+use bitgraphs::bitset::BitVec;
+
+fn parse(text: &str) -> Result<graph::Graph, GraphError> {
+    graph::read_csv(&mut io::BufReader::new(text.as_bytes()))
+}
+
+#[test]
+fn test_try_mutations() {
+    let mut g = digraph::new(3);
+    assert!(g.try_add_edge(0, 3) == Err(GraphError::OutOfBounds { vertex: 3, len: 3 }));
+    assert!(g.try_add_edges(0, &[1, 5]).is_err());
+    assert!(g.try_add_edge(0, 1).is_ok());
+    assert!(g.try_contract((1, 0)) == Err(GraphError::MissingEdge(1, 0)));
+    assert!(g.try_contract((0, 1)).is_ok());
+    assert!(g.try_induce(&BitVec::from_elem(2, true)) ==
+            Err(GraphError::SizeMismatch { len: 2, expected: 3 }));
+    assert!(g.try_remove_vertex(7, Removal::Tombstone).is_err());
+}
+
+#[test]
+fn test_read_csv_errors() {
+    assert!(parse("0,1\n1,0\n").is_ok());
+    assert!(parse("0,1\n0,0\n") == Err(GraphError::Asymmetric(0, 1)));
+    assert!(parse("0,1\n1\n") == Err(GraphError::RaggedRow { row: 1, len: 1, expected: 2 }));
+    match parse("0,1\n1,x\n") {
+        Err(GraphError::Parse { line: 2, .. }) => {}
+        r => panic!("unexpected {:?}", r)
+    }
+    assert!(digraph::read_csv(&mut io::BufReader::new("0,1,1\n0,0\n".as_bytes())).is_err());
+}
+
+#[test]
+fn test_dot_missing_attrs() {
+    let g = graph::complete(3);
+    let mut node_attrs = HashMap::new();
+    node_attrs.insert(1, vec![("color".to_string(), "red".to_string())].into_iter().collect());
+    let dot = g.serialize_dot(Some(&node_attrs), Some(&HashMap::new()));
+    assert!(dot.contains("1 [id=1,color=\"red\"]") && dot.contains("0 [id=0]"));
+}
@@ -0,0 +1,92 @@
+use std::io;
+use bitgraphs::{GraphRef, GraphMut, GraphError, LoopPolicy};
+use bitgraphs::{digraph, graph};
+use bitgraphs::formats::{graph6, DENSE_LIMIT};
+
+fn sorted_edges<G: GraphRef>(g: &G) -> Vec<(usize, usize)> {
+    g.edges().collect()
+}
+
+#[test]
+fn test_graph6_and_sparse6() {
+    // the examples of the format description
+    let g = graph6::parse_graph6("DQc").unwrap();
+    assert_eq!(sorted_edges(&g), vec![(0, 2), (0, 4), (1, 3), (3, 4)]);
+    assert_eq!(graph6::to_graph6(&g), "DQc");
+    let s = graph6::parse_sparse6(":Fa@x^").unwrap();
+    assert_eq!(sorted_edges(&s), vec![(0, 1), (0, 2), (1, 2), (5, 6)]);
+    assert_eq!(graph6::to_sparse6(&s), ":Fa@x^");
+    // sizes past one byte, and the padding that must not read as a loop
+    let mut big = graph::new(300);
+    big.add_edges(0, &[1, 150, 299]);
+    assert_eq!(graph6::parse_graph6(&graph6::to_graph6(&big)).unwrap(), big);
+    assert_eq!(graph6::parse_sparse6(&graph6::to_sparse6(&big)).unwrap(), big);
+    let mut four = graph::new(4);
+    four.add_edge(1, 2);
+    assert_eq!(graph6::parse_sparse6(&graph6::to_sparse6(&four)).unwrap(), four);
+    assert_eq!(graph6::parse_graph6("D!c"), Err(GraphError::Parse {
+        line: 1, msg: "byte 1 is '!', outside '?' to '~'".to_string()
+    }));
+}
+
+#[test]
+fn test_digraph6() {
+    let d = graph6::parse_digraph6("&DI?AO?").unwrap();
+    assert_eq!(sorted_edges(&d), vec![(0, 2), (0, 4), (3, 1), (3, 4)]);
+    assert_eq!(graph6::to_digraph6(&d), "&DI?AO?");
+    let mut out = Vec::new();
+    graph6::write_digraph6(&d, &mut out).unwrap();
+    graph6::write_digraph6(&digraph::new(2), &mut out).unwrap();
+    let read: Vec<_> = graph6::read_digraphs(io::Cursor::new(out)).collect();
+    assert_eq!(read, vec![Ok(d), Ok(digraph::new(2))]);
+}
+
+#[test]
+fn test_read_graphs() {
+    let text = ">>graph6<<DQc\n\n:Fa@x^\nD\nA_\n";
+    let read: Vec<_> = graph6::read_graphs(io::Cursor::new(text)).collect();
+    assert_eq!(read.len(), 4);
+    assert!(read[0].is_ok() && read[1].as_ref().unwrap().len() == 7);
+    assert_eq!(read[2], Err(GraphError::Parse {
+        line: 4, msg: "0 bytes of edges, expected 2".to_string()
+    }));
+    assert!(read[3].as_ref().unwrap().has_edge(1, 0));
+}
+
+#[test]
+fn test_malformed_headers() {
+    // sizes whose matrices cannot exist fail as parse errors, before anything is allocated
+    let too_many = |n: u64| format!("{} vertices, too many to address their pairs", n);
+    let max = (1 << 36) - 1;
+    assert_eq!(graph6::parse_graph6("~~~~~~~~"), Err(GraphError::Parse {
+        line: 1, msg: too_many(max)
+    }));
+    assert_eq!(graph6::parse_digraph6("&~~~~~~~~"), Err(GraphError::Parse {
+        line: 1, msg: too_many(max)
+    }));
+    assert_eq!(graph6::parse_sparse6(":~~~~~~~~"), Err(GraphError::Parse {
+        line: 1, msg: format!("{} vertices, more than the {} of a dense graph", max, DENSE_LIMIT)
+    }));
+    assert!(graph6::parse_graph6("~??~??").is_err() && graph6::parse_sparse6(":~?").is_err());
+}
+
+#[test]
+fn test_loop_round_trip() {
+    // the loops sparse6 and digraph6 encode come back under Allow, and fail under Forbid
+    let mut g = graph::with_loops(5, LoopPolicy::Allow);
+    g.add_edges(2, &[2, 4]);
+    let s = graph6::to_sparse6(&g);
+    assert_eq!(graph6::parse_sparse6_with(&s, LoopPolicy::Allow).unwrap(), g);
+    assert!(!graph6::parse_sparse6(&s).unwrap().has_edge(2, 2));
+    assert_eq!(graph6::parse_sparse6_with(&s, LoopPolicy::Forbid), Err(GraphError::Parse {
+        line: 1, msg: "loop at 2 is forbidden".to_string()
+    }));
+    let mut d = digraph::with_loops(3, LoopPolicy::Allow);
+    d.add_edges(0, &[0, 1]);
+    let text = format!("{}\n{}\n", graph6::to_digraph6(&d), graph6::to_digraph6(&d));
+    let read: Vec<_> = graph6::read_digraphs_with(io::Cursor::new(text), LoopPolicy::Allow)
+        .collect();
+    assert_eq!(read, vec![Ok(d.clone()), Ok(d)]);
+    let read: Vec<_> = graph6::read_graphs_with(io::Cursor::new(s), LoopPolicy::Allow).collect();
+    assert_eq!(read, vec![Ok(g)]);
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::io;
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, LoopPolicy, NodeAttrs, EdgeAttrs};
+use bitgraphs::{digraph, graph};
+use bitgraphs::formats::{graphml, DENSE_LIMIT};
+
+fn attrs(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_graphml_round_trip() {
+    let mut g = graph::new(8);
+    g.add_edges(2, &[5, 7]);
+    let (c, map) = g.compressed();
+    let mut nodes = NodeAttrs::new();
+    nodes.insert(1, attrs(&[("label", "five & <co>")]));
+    let mut edges = EdgeAttrs::new();
+    edges.insert((0, 2), attrs(&[("weight", "1.5")]));
+    let mut out = Vec::new();
+    graphml::write_graphml(&c, Some(&map), Some(&nodes), Some(&edges), &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.contains("edgedefault=\"undirected\"") && text.contains("<node id=\"n7\"/>"));
+    let read = graphml::read_graphml(&mut io::BufReader::new(text.as_bytes())).unwrap();
+    assert_eq!(read.graph, c);
+    assert_eq!(read.index_map(), Some(map));
+    assert_eq!((read.node_attrs, read.edge_attrs), (nodes, edges));
+    let mut d = digraph::new(3);
+    d.add_edge(2, 0);
+    let mut out = Vec::new();
+    graphml::write_graphml(&d, None, None, None, &mut out).unwrap();
+    let read = graphml::read_graphml_directed(&mut io::BufReader::new(&out[..])).unwrap();
+    assert!(read.graph == d && read.ids == vec!["n0", "n1", "n2"]);
+}
+
+#[test]
+fn test_graphml_keys() {
+    let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- exported by hand -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:y="http://www.yworks.com/xml">
+  <key id="d0" for="node" attr.name="color" attr.type="string"><default>gray</default></key>
+  <key id="d1" for="edge" attr.name="kind"/>
+  <graph id="G" edgedefault="directed">
+    <node id="alpha"><data key="d0">red</data><y:ShapeNode/></node>
+    <node id="beta"/>
+    <node id="gamma"><data key="d0"><![CDATA[<blue>]]></data></node>
+    <edge source="beta" target="alpha"><data key="d1">a &amp; b</data></edge>
+    <edge source='gamma' target='beta'/>
+  </graph>
+</graphml>"#;
+    let read = graphml::read_graphml(&mut io::BufReader::new(text.as_bytes())).unwrap();
+    assert_eq!(read.ids, vec!["alpha", "beta", "gamma"]);
+    assert!(read.graph.has_edge(0, 1) && read.graph.has_edge(1, 2) && read.index_map().is_none());
+    assert_eq!(read.node_attrs[&1], attrs(&[("color", "gray")]));
+    assert_eq!(read.node_attrs[&2], attrs(&[("color", "<blue>")]));
+    assert_eq!(read.edge_attrs[&(0, 1)], attrs(&[("kind", "a & b")]));
+    let d = graphml::read_graphml_directed(&mut io::BufReader::new(text.as_bytes())).unwrap();
+    assert!(d.graph.has_edge(1, 0) && !d.graph.has_edge(0, 1));
+    assert!(d.edge_attrs.contains_key(&(1, 0)));
+}
+
+#[test]
+fn test_graphml_errors() {
+    let read = |text: &str| graphml::read_graphml(&mut io::BufReader::new(text.as_bytes()))
+        .map(|r| r.graph);
+    let parse = |line, msg: &str| Err(GraphError::Parse { line, msg: msg.to_string() });
+    assert_eq!(read("<graphml><graph>\n<node id=\"a\"/>\n<edge source=\"a\" target=\"b\"/>"),
+               parse(3, "edge to unknown node \"b\""));
+    assert_eq!(read("<graph>\n<node id=\"a\"/><node id=\"a\"/>"),
+               parse(2, "node id \"a\" is used twice"));
+    assert_eq!(read("<graph>\n<node id=\"a\"><data key=\"x\">1</data></node>"),
+               parse(2, "undeclared key \"x\""));
+    assert_eq!(read("<graph>\n\n<node id=\"a&nbsp;\"/>"), parse(3, "unknown entity &nbsp;"));
+}
+
+#[test]
+fn test_graphml_loops_and_size() {
+    // loops and their data survive under Allow, fail under Forbid and are dropped by default
+    let mut g = graph::with_loops(3, LoopPolicy::Allow);
+    g.add_edges(1, &[1, 2]);
+    let mut edges = EdgeAttrs::new();
+    edges.insert((1, 1), attrs(&[("weight", "3")]));
+    let mut out = Vec::new();
+    graphml::write_graphml(&g, None, None, Some(&edges), &mut out).unwrap();
+    let read = |loops| graphml::read_graphml_with(&mut io::BufReader::new(&out[..]), loops);
+    let back = read(LoopPolicy::Allow).unwrap();
+    assert!(back.graph == g && back.edge_attrs == edges);
+    assert_eq!(read(LoopPolicy::Forbid).err(), Some(GraphError::SelfLoop(1)));
+    let dropped = graphml::read_graphml(&mut io::BufReader::new(&out[..])).unwrap();
+    assert!(!dropped.graph.has_edge(1, 1) && dropped.edge_attrs.is_empty());
+    // one node past the dense limit fails at its line, before the matrix is allocated
+    let mut text = "<graphml><graph>\n".to_string();
+    for i in 0..=DENSE_LIMIT {
+        text.push_str(&format!("<node id=\"x{}\"/>\n", i));
+    }
+    text.push_str("</graph></graphml>\n");
+    assert_eq!(graphml::read_graphml(&mut io::BufReader::new(text.as_bytes())).err(),
+               Some(GraphError::Parse {
+                   line: DENSE_LIMIT + 2,
+                   msg: format!("{} vertices, more than the {} of a dense graph",
+                                DENSE_LIMIT + 1, DENSE_LIMIT)
+               }));
+}
@@ -0,0 +1,71 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut};
+use bitgraphs::{hybrid, utils};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_hybrid() {
+    let mut g = hybrid::with_density(200, false, 0.05);
+    // a star: the center passes the threshold, the leaves stay sparse
+    for i in 1..50 {
+        g.add_edge(0, i);
+    }
+    g.add_edge(50, 51);
+    assert!(g.verify());
+    assert!(g.dense_rows() == 1);
+    assert!(g.has_edge(7, 0) && g.has_edge(51, 50) && !g.has_edge(1, 2));
+    let d = utils::dfs(&g, 3, &mut |_| {});
+    assert!(d[49] >= 0 && d[50] == -1);
+    let (dist, _) = utils::bfs(&g, 3, &mut |_,_,_| {});
+    assert!(dist[0] == 1 && dist[49] == 2);
+    assert!(utils::greedy_color(&g, &(0..200).collect::<Vec<_>>()).len() == 2);
+    assert!(utils::is_clique(&g, &BitVec::from_fn(200, |i| i == 50 || i == 51)));
+    for i in 1..50 {
+        g.remove_edge(i, 0);
+    }
+    assert!(g.dense_rows() == 0 && g.verify());
+}
+
+#[test]
+fn test_hybrid_directed() {
+    let mut g = hybrid::new_directed(6);
+    g.add_edges(0, &[1, 2]);
+    g.add_edge(2, 3);
+    assert!(g.has_edge(0, 2) && !g.has_edge(2, 0));
+    assert!(g.in_neighbors(3)[2]);
+    g.contract((0, 2));
+    assert!(g.has_edge(0, 3) && !g.in_neighbors(2).any() && g.verify());
+    let (c, map) = g.compressed();
+    assert!(map == vec![0, 1, 3] && c.has_edge(0, 2));
+    assert!(g.complement().has_edge(3, 0));
+}
+
+#[test]
+fn test_hybrid_large_sparse() {
+    // a path on a million vertices: traversals and coloring walk the lists, not built rows
+    let n = 1_000_000;
+    let mut g = hybrid::new(n);
+    for i in 1..n {
+        g.add_edge(i - 1, i);
+    }
+    assert!(g.dense_rows() == 0 && g.edge_count() == n - 1);
+    let (dist, parents) = utils::bfs(&g, 0, &mut |_,_,_| {});
+    assert!(dist[n - 1] == (n - 1) as i32 && parents[n - 1] == n - 2);
+    assert!(utils::dfs(&g, n - 1, &mut |_| {})[0] == (n - 1) as i32);
+    assert_eq!(g.edges().nth(n - 2), Some((n - 2, n - 1)));
+    let order: Vec<usize> = (0..n).collect();
+    assert_eq!(utils::greedy_color(&g, &order).len(), 2);
+    assert!(utils::is_clique(&g, &BitVec::from_fn(n, |i| i == 7 || i == 8)));
+}
+
+#[test]
+fn test_hybrid_loops() {
+    // loops are dropped, and contracting an edge does not leave one behind
+    let mut g = hybrid::new(5);
+    g.add_edge(2, 2);
+    assert!(!g.has_edge(2, 2) && g.edge_count() == 0);
+    g.add_edges(3, &[0, 1, 4]);
+    g.add_edge(4, 1);
+    g.contract((3, 4));
+    assert_eq!(g.edges().collect::<Vec<_>>(), vec![(0, 3), (1, 3)]);
+    assert!(!g.has_edge(3, 3) && g.degree(4) == 0 && g.verify());
+}
@@ -0,0 +1,36 @@
+use bitgraphs::{GraphRef, GraphError};
+use bitgraphs::hypergraph;
+use bitgraphs::bitset::BitVec;
+
+fn set(n: usize, vs: &[usize]) -> BitVec {
+    BitVec::from_fn(n, |v| vs.contains(&v))
+}
+
+#[test]
+fn test_hypergraph() {
+    // edges {0, 1, 2}, {2, 3}, {4}
+    let mut h = hypergraph::from_edges(5, &[&[0, 1, 2], &[2, 3], &[4]]).unwrap();
+    assert!(h.edge_count() == 3 && h.contains(1, 3) && !h.contains(0, 3) && h.degree(2) == 2);
+    let p = h.primal();
+    assert!(p.has_edge(0, 1) && p.has_edge(2, 3) && !p.has_edge(1, 3) && !p.has_edge(4, 4));
+    let d = h.dual();
+    assert!(d.len() == 3 && d.edge_count() == 5 && d.edge(2) == set(3, &[0, 1]));
+    assert!(d.dual() == h);
+    let levi = h.incidence_graph();
+    assert!(levi.len() == 8 && levi.has_edge(2, 6) && levi.has_edge(4, 7) && !levi.has_edge(0, 6));
+
+    assert!(h.remove_edge(0) == Some(2));
+    assert!(h.edge(0) == set(5, &[4]) && h.edge_count() == 2);
+    let e = hypergraph::from_edges(2, &[&[0, 2]]);
+    assert!(e == Err(GraphError::OutOfBounds { vertex: 2, len: 2 }));
+}
+
+#[test]
+fn test_transversals() {
+    let h = hypergraph::from_edges(5, &[&[0, 1, 2], &[2, 3], &[4]]).unwrap();
+    assert!(h.is_transversal(&set(5, &[2, 4])) && h.is_minimal_transversal(&set(5, &[2, 4])));
+    let extra = set(5, &[1, 2, 4]);
+    assert!(h.is_transversal(&extra) && !h.is_minimal_transversal(&extra));
+    assert!(h.is_minimal_transversal(&set(5, &[0, 3, 4])));
+    assert!(!h.is_transversal(&set(5, &[0, 1, 3])));
+}
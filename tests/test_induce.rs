@@ -1,6 +1,6 @@
-use bitgraphs::BitGraph;
+use bitgraphs::{BitGraph, GraphRef, GraphMut};
 use bitgraphs::graph;
-use std::collections::BitVec;
+use bitgraphs::bitset::BitVec;
 
 #[test]
 fn test_induce() {
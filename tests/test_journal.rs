@@ -0,0 +1,65 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, Removal};
+use bitgraphs::{digraph, graph, journal};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_undo_redo() {
+    let base: graph::Graph = (0..6).map(|i| (i, (i + 1) % 6)).collect();
+    let mut j = journal::new(base.clone());
+    j.add_edge(0, 3);
+    j.add_edge(0, 3);
+    j.contract((0, 1));
+    j.induce(&BitVec::from_fn(6, |v| v != 4));
+    j.remove_vertex(2, Removal::SwapRemove);
+    let end = j.graph().clone();
+    assert!(j.verify() && j.len() == 5);
+    while j.undo() {}
+    assert_eq!(*j.graph(), base);
+    assert!(!j.can_undo() && j.can_redo());
+    while j.redo() {}
+    assert_eq!(*j.graph(), end);
+    // a new step after undoing drops what could be redone
+    j.undo();
+    j.remove_edge(0, 5);
+    assert!(!j.can_redo());
+}
+
+#[test]
+fn test_checkpoints() {
+    let mut d = digraph::new(4);
+    d.add_edges(0, &[1, 2, 3]);
+    let mut j = journal::new(d.clone());
+    j.checkpoint("start");
+    let v = j.add_vertex();
+    j.add_edge(v, 0);
+    j.checkpoint("added");
+    j.contract((0, 2));
+    j.remove_vertex(1, Removal::Tombstone);
+    j.rollback_to("start").unwrap();
+    assert_eq!(*j.graph(), d);
+    j.rollback_to("added").unwrap();
+    assert!(j.len() == 5 && j.has_edge(4, 0) && j.has_edge(0, 2));
+    j.remove_edge(0, 1);
+    assert_eq!(j.rollback_to("gone"), Err(GraphError::UnknownCheckpoint("gone".to_string())));
+    assert!(j.rollback_to("start").is_ok() && j.graph().edge_count() == 3);
+}
+
+#[test]
+fn test_transactions() {
+    let mut j = journal::new(graph::new(5));
+    j.add_edge(0, 1);
+    let r: Result<(), GraphError> = j.transaction(|j| {
+        j.add_edge(1, 2);
+        j.try_add_edge(2, 9)
+    });
+    assert!(r.is_err() && !j.has_edge(1, 2) && j.has_edge(0, 1));
+    let r: Result<(), GraphError> = j.transaction(|j| {
+        j.add_edges(2, &[3, 4]);
+        j.contract((2, 3));
+        Ok(())
+    });
+    assert!(r.is_ok() && j.has_edge(2, 4));
+    // the whole transaction is one step
+    assert!(j.undo() && j.graph().edge_count() == 1);
+    assert!(j.undo() && !j.undo());
+}
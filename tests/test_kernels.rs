@@ -0,0 +1,34 @@
+use bitgraphs::bitset::BitVec;
+use bitgraphs::bitset::kernels;
+
+#[test]
+fn test_counts() {
+    let a = BitVec::from_fn(130, |i| i % 2 == 0);
+    let b = BitVec::from_fn(130, |i| i % 3 == 0);
+    assert!(kernels::count_ones(&a) == 65);
+    assert!(kernels::and_count(&a, &b) == 22);
+    assert!(kernels::andnot_count(&a, &b) == 43);
+    assert!(kernels::or_count(&a, &b) == 65 + 44 - 22);
+    assert!(kernels::and_count_rows(&[&a, &b, &a]) == 22);
+    assert!(!kernels::is_disjoint(&a, &b));
+    assert!(kernels::is_subset(&BitVec::from_fn(130, |i| i % 6 == 0), &b));
+}
+
+#[test]
+fn test_scans() {
+    let a = BitVec::from_fn(200, |i| i == 70 || i == 128 || i == 199);
+    assert!(kernels::first_one(&a) == Some(70));
+    assert!(kernels::next_one(&a, 71) == Some(128));
+    assert!(kernels::next_one(&a, 200).is_none());
+    assert!(kernels::ones(&a).collect::<Vec<_>>() == vec![70, 128, 199]);
+    let b = BitVec::from_fn(200, |i| i == 128);
+    assert!(kernels::ones_andnot(&a, &b).collect::<Vec<_>>() == vec![70, 199]);
+    assert!(kernels::first_and(&a, &b) == Some(128));
+    assert!(kernels::first_disjoint(&b, vec![&a, &b, &BitVec::from_elem(200, false)]) == Some(2));
+
+    let mut seen = BitVec::from_elem(200, false);
+    seen.set(128, true);
+    let mut marked = vec![];
+    assert!(kernels::mark_new(&mut seen, &a, |i| marked.push(i)) == 2);
+    assert!(marked == vec![70, 199] && seen[70] && seen[199]);
+}
@@ -0,0 +1,46 @@
+use std::io;
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, Removal};
+use bitgraphs::{graph, digraph, labeled};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_labels() {
+    let mut g = labeled::new(graph::new(0));
+    g.add_edge_by_label("alice", "bob");
+    g.add_edge_by_label("bob", "carol");
+    g.intern("dave");
+    assert!(g.len() == 4 && g.has_edge_by_label("carol", "bob") && g.has_edge(0, 1));
+    assert!(g.index("dave") == Some(3) && g.label(2) == "carol");
+    assert!(g.remove_edge_by_label("bob", "erin") == Err(GraphError::UnknownLabel("erin".into())));
+    assert!(g.set_label(0, "bob") == Err(GraphError::DuplicateLabel("bob".into())));
+
+    let (c, _) = g.compressed();
+    assert!(c.labels() == ["alice", "bob", "carol"] && c.has_edge_by_label("alice", "bob"));
+    let r = g.reordered(&[3, 2, 1, 0]);
+    assert!(r.label(0) == "dave" && r.has_edge_by_label("bob", "carol") && r.verify());
+    let mut i = g.clone();
+    i.induce(&BitVec::from_fn(4, |v| v != 1));
+    assert!(i.label(1) == "bob" && !i.has_edge_by_label("alice", "bob"));
+    assert!(g.remove_vertex(0, Removal::SwapRemove) == Some(3));
+    assert!(g.index("dave") == Some(0) && g.index("alice").is_none() && g.verify());
+
+    let dup = labeled::with_labels(graph::new(2), vec!["x", "x"]);
+    assert!(dup == Err(GraphError::DuplicateLabel("x".into())));
+}
+
+#[test]
+fn test_labeled_output() {
+    let mut g = labeled::with_labels(digraph::new(2), vec!["a \"b\"", "c,d"]).unwrap();
+    g.add_edge(0, 1);
+    let dot = g.serialize_dot(None, None);
+    assert!(dot.contains("\"a \\\"b\\\"\" -> \"c,d\""));
+    let mut out = Vec::new();
+    g.write_csv(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "\"a \"\"b\"\"\",\"c,d\"\n0,0\n1,0\n");
+
+    // the matrix written by graph::write_csv reads back as the same graph
+    let mut out = Vec::new();
+    graph::write_csv(g.graph(), &mut out).unwrap();
+    let back = digraph::read_csv(&mut io::BufReader::new(&out[..])).unwrap();
+    assert!(back.has_edge(0, 1) && !back.has_edge(1, 0));
+}
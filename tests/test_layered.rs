@@ -0,0 +1,34 @@
+use bitgraphs::{layered, utils};
+use bitgraphs::layered::Combine;
+
+#[test]
+fn test_layers() {
+    // 0 -knows-> 1 -knows-> 2, 0 -likes-> 2, 0 -likes-> 1
+    let (knows, likes) = (0, 1);
+    let mut g = layered::new(4, 2);
+    g.add_edge(0, 1, knows);
+    g.add_edge(1, 2, knows);
+    g.add_edge(0, 2, likes);
+    g.add_edge(0, 1, likes);
+    assert!(g.has_edge(0, 2, likes) && !g.has_edge(0, 2, knows));
+    assert_eq!(g.edge_labels(0, 1), vec![knows, likes]);
+    let both = g.out_intersection(0, &[knows, likes]);
+    assert!(both[1] && !both[2]);
+    let any = g.out_union(0, &[knows, likes]);
+    assert!(any[1] && any[2] && !any[3]);
+    assert!(g.in_union(2, &[knows, likes])[1] && !g.in_intersection(2, &[knows, likes])[0]);
+}
+
+#[test]
+fn test_layer_bfs() {
+    let mut g = layered::new(4, 3);
+    g.add_edge(0, 1, 0);
+    g.add_edge(1, 2, 1);
+    g.add_edge(2, 3, 2);
+    let (dist, _) = utils::bfs(&g.view(&[0, 1], Combine::Union), 0, &mut |_, _, _| {});
+    assert_eq!(dist, vec![0, 1, 2, -1]);
+    let (dist, _) = utils::bfs(&g.view(&[0, 1], Combine::Intersection), 0, &mut |_, _, _| {});
+    assert_eq!(dist, vec![0, -1, -1, -1]);
+    let (dist, _) = utils::bfs(g.layer(2), 2, &mut |_, _, _| {});
+    assert_eq!(dist[3], 1);
+}
@@ -0,0 +1,74 @@
+use std::io;
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, LoopPolicy};
+use bitgraphs::{graph, digraph, hybrid, views};
+
+#[test]
+fn test_policies() {
+    let mut g = graph::new(3);
+    g.add_edge(1, 1);
+    assert!(!g.has_edge(1, 1) && g.verify());
+    assert!(!g.complement().has_edge(2, 2));
+
+    let mut f = graph::with_loops(3, LoopPolicy::Forbid);
+    assert!(f.try_add_edge(2, 2) == Err(GraphError::SelfLoop(2)));
+
+    let mut a = digraph::with_loops(3, LoopPolicy::Allow);
+    a.add_edge(0, 0);
+    a.add_edge(0, 1);
+    assert!(a.has_edge(0, 0) && a.verify());
+    let c = a.complement();
+    assert!(!c.has_edge(0, 0) && c.has_edge(1, 1) && c.has_edge(1, 0));
+    assert!(a.set_loop_policy(LoopPolicy::Forbid) == Err(GraphError::SelfLoop(0)));
+    assert!(a.set_loop_policy(LoopPolicy::Ignore).is_ok() && !a.has_edge(0, 0));
+}
+
+#[test]
+fn test_contract_loops() {
+    // an automaton: 0 -> 1 -> 2, 1 -> 1
+    let mut a = digraph::with_loops(3, LoopPolicy::Allow);
+    a.add_edges(0, &[1]);
+    a.add_edges(1, &[1, 2]);
+    let mut b = a.clone();
+    b.set_loop_policy(LoopPolicy::Ignore).unwrap();
+    a.contract((0, 1));
+    assert!(a.has_edge(0, 0) && a.has_edge(0, 2) && !a.in_neighbors(1).any() && a.verify());
+    b.contract((0, 1));
+    assert!(!b.has_edge(0, 0) && b.has_edge(0, 2) && b.verify());
+
+    let mut g = graph::complete(3);
+    g.contract((0, 1));
+    assert!(!g.has_edge(0, 0) && g.has_edge(0, 2) && !g.has_edge(1, 2) && g.verify());
+}
+
+#[test]
+fn test_loop_io() {
+    let text = "1,1\n1,0\n";
+    let read = |loops| graph::read_csv_with(&mut io::BufReader::new(text.as_bytes()), loops);
+    assert!(read(LoopPolicy::Forbid) == Err(GraphError::SelfLoop(0)));
+    assert!(!read(LoopPolicy::Ignore).unwrap().has_edge(0, 0));
+    let g = read(LoopPolicy::Allow).unwrap();
+    assert!(g.has_edge(0, 0));
+    let dot = g.serialize_dot(None, None);
+    assert!(dot.starts_with("graph {") && dot.contains("0 -- 0"));
+    assert!(graph::complete(2).serialize_dot(None, None).starts_with("strict graph {"));
+}
+
+#[test]
+fn test_complement_views() {
+    // the view agrees with the owned complement under either policy
+    for &loops in [LoopPolicy::Allow, LoopPolicy::Ignore].iter() {
+        let mut g = graph::with_loops(4, loops);
+        g.add_edges(0, &[0, 1, 3]);
+        g.add_edge(2, 2);
+        let (c, v) = (g.complement(), views::complement(&g));
+        assert_eq!(v.loop_policy(), loops);
+        for i in 0..4 {
+            assert_eq!(v.out_neighbor_iter(i).collect::<Vec<_>>(),
+                       c.out_neighbor_iter(i).collect::<Vec<_>>());
+            assert!(v.out_degree(i) == c.out_degree(i) && v.out_neighbors(i) == c.out_neighbors(i));
+            assert!((0..4).all(|j| v.has_edge(i, j) == c.has_edge(i, j)));
+        }
+    }
+    let h = hybrid::new(3);
+    assert!(h.loop_policy() == LoopPolicy::Ignore && !views::complement(&h).has_edge(1, 1));
+}
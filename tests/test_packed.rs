@@ -0,0 +1,28 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut};
+use bitgraphs::{graph, packed, utils};
+use bitgraphs::packed::PackedGraph;
+
+#[test]
+fn test_packed() {
+    let mut g = packed::new(5);
+    g.add_edge(1, 2);
+    g.add_edges(2, &[3,4]);
+    assert!(g.verify());
+    assert!(g.has_edge(2,1) && g.has_edge(4,2));
+    assert!(g.out_degree(2) == 3);
+    assert!(g.in_neighbors(4) == g.in_neighbors(3));
+    let c = g.complement();
+    assert!(!c.has_edge(1,2) && c.has_edge(0,1) && !c.has_edge(0,0));
+    g.contract((2, 4));
+    assert!(!g.has_edge(2, 4) && g.has_edge(1, 2) && !g.out_neighbors(4).any());
+}
+
+#[test]
+fn test_packed_roundtrip() {
+    let g = graph::erdos_renyi(40, 0.3);
+    let p = PackedGraph::from_graph(&g);
+    assert!(p.to_graph() == g);
+    let order: Vec<usize> = (0..40).rev().collect();
+    assert!(p.reordered(&order).to_graph() == g.reordered(&order));
+    assert!(utils::is_clique(&packed::complete(6), &packed::complete(6).neighbors(0)));
+}
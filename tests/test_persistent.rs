@@ -0,0 +1,40 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut, Removal};
+use bitgraphs::{digraph, graph, persistent};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_sharing() {
+    let base = persistent::from_graph(&graph::complete(100));
+    let mut snap = base.clone();
+    assert_eq!(snap.shared_rows(&base), 100);
+    snap.remove_edge(3, 7);
+    assert!(!snap.has_edge(7, 3) && base.has_edge(7, 3));
+    assert_eq!(snap.shared_rows(&base), 98);
+    // writing a bit that is already set copies nothing
+    let mut same = base.clone();
+    same.add_edge(1, 2);
+    assert_eq!(same.shared_rows(&base), 100);
+    let mut c = base.clone();
+    c.contract((0, 1));
+    assert!(c.verify() && !c.has_edge(0, 0) && !c.out_neighbors(1).any() && base.verify());
+}
+
+#[test]
+fn test_persistent_directed() {
+    let mut d = digraph::new(4);
+    d.add_edges(0, &[1, 2]);
+    d.add_edge(3, 0);
+    let p = persistent::from_graph(&d);
+    assert!(p.is_directed() && p.has_edge(3, 0) && !p.has_edge(0, 3) && p.in_degree(0) == 1);
+    let r = p.reordered(&[3, 2, 1, 0]);
+    assert!(r.has_edge(0, 3) && r.has_edge(3, 1) && r.verify());
+    let mut q = p.clone();
+    q.induce(&BitVec::from_fn(4, |v| v != 1));
+    assert!(!q.has_edge(0, 1) && q.has_edge(0, 2) && q.verify() && p.has_edge(0, 1));
+    assert!(q.remove_vertex(0, Removal::SwapRemove) == Some(3));
+    assert!(q.edge_count() == 0 && q.len() == 3 && q.verify());
+    assert!(p.complement().edge_count() == 12 - 3);
+    let mut n = persistent::new_directed(2);
+    n.add_edge(0, 1);
+    assert!(n.add_vertex() == 2 && n.has_edge(0, 1) && n.verify());
+}
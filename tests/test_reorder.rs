@@ -1,4 +1,4 @@
-use bitgraphs::BitGraph;
+use bitgraphs::{BitGraph, GraphRef};
 use bitgraphs::graph;
 
 #[test]
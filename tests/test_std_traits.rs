@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+use bitgraphs::{GraphRef, GraphMut};
+use bitgraphs::{graph, digraph};
+use bitgraphs::graph::Graph;
+use bitgraphs::digraph::DiGraph;
+
+#[test]
+fn test_collect() {
+    let g: Graph = vec![(0, 1), (1, 3)].into_iter().collect();
+    assert!(g.len() == 4 && g[(1, 0)] && g[(3, 1)] && !g[(0, 3)]);
+    let mut h = graph::new(2);
+    h.add_edge(0, 1);
+    h.extend(vec![(3, 1)]);
+    assert!(g == h);
+    assert_eq!(format!("{}", g), "0: 1\n1: 0 3\n2:\n3: 1\n");
+
+    let mut d: DiGraph = vec![(2, 0)].into_iter().collect();
+    assert!(d[(2, 0)] && !d[(0, 2)] && d.len() == 3);
+    d.extend(vec![(0, 1)]);
+    assert_eq!(format!("{}", d), "0: 1\n1:\n2: 0\n");
+    assert!(d != d.transpose() && d == d.transpose().transpose());
+}
+
+#[test]
+fn test_hash() {
+    let mut seen = HashSet::new();
+    seen.insert(graph::complete(3));
+    seen.insert(graph::complete(3));
+    seen.insert(graph::new(3));
+    assert_eq!(seen.len(), 2);
+    let mut arcs = HashSet::new();
+    arcs.insert(digraph::new(2));
+    arcs.insert(vec![(0, 1)].into_iter().collect::<DiGraph>());
+    assert!(arcs.len() == 2 && arcs.contains(&digraph::new(2)));
+}
@@ -0,0 +1,54 @@
+use std::fs::File;
+use std::io;
+use bitgraphs::{GraphRef, GraphMut, GraphError};
+use bitgraphs::{digraph, tournament};
+use bitgraphs::tournament::Tournament;
+
+fn check_cycle(t: &Tournament, cycle: &[usize]) {
+    let mut sorted = cycle.to_vec();
+    sorted.sort();
+    assert_eq!(sorted, (0..t.len()).collect::<Vec<_>>());
+    // a single vertex is a cycle without arcs
+    let k = cycle.len();
+    assert!(k == 1 || (0..k).all(|i| t.has_edge(cycle[i], cycle[(i + 1) % k])));
+}
+
+#[test]
+fn test_tourney_csv() {
+    let f = File::open("tests/data/tourney.csv").unwrap();
+    let t = tournament::read_csv(&mut io::BufReader::new(f)).unwrap();
+    assert_eq!(t.score_sequence(), vec![1, 1, 2, 2]);
+    assert!(!t.is_transitive() && t.is_strongly_connected());
+    assert!(!t.kings().is_empty());
+    check_cycle(&t, &t.hamiltonian_cycle().unwrap());
+
+    let mut g = digraph::new(3);
+    g.add_edge(0, 1);
+    g.add_edge(1, 0);
+    g.add_edge(1, 2);
+    let e = tournament::from_digraph(g).unwrap_err();
+    assert!(e == GraphError::ArcCount { pair: (0, 1), arcs: 2 });
+}
+
+#[test]
+fn test_tournament_paths() {
+    let mut t = tournament::transitive(5);
+    assert!(t.is_transitive() && t.kings() == vec![0]);
+    assert_eq!(t.hamiltonian_path(), vec![0, 1, 2, 3, 4]);
+    assert!(t.hamiltonian_cycle().is_none());
+    t.reverse(0, 4);
+    check_cycle(&t, &t.hamiltonian_cycle().unwrap());
+
+    for n in 1..20 {
+        let r = tournament::random(n);
+        assert!(tournament::from_digraph(r.digraph().clone()).is_ok());
+        let path = r.hamiltonian_path();
+        assert!(path.windows(2).all(|w| r.has_edge(w[0], w[1])) && path.len() == n);
+        let best = (0..n).max_by_key(|&v| r.score(v)).unwrap();
+        assert!(r.kings().contains(&best));
+        match r.hamiltonian_cycle() {
+            Some(c) => check_cycle(&r, &c),
+            None => assert!(!r.is_strongly_connected())
+        }
+    }
+}
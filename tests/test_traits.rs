@@ -0,0 +1,57 @@
+use std::borrow::Cow;
+use bitgraphs::{GraphRef, GraphMut, GraphError, LoopPolicy};
+use bitgraphs::{graph, utils};
+use bitgraphs::bitset::BitVec;
+
+/// A read-only cycle that stores nothing but its length.
+struct Cycle(usize);
+
+impl GraphRef for Cycle {
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn is_directed(&self) -> bool {
+        false
+    }
+    fn in_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        self.out_neighbors(id)
+    }
+    fn out_neighbors(&self, id: usize) -> Cow<'_, BitVec> {
+        let n = self.0;
+        Cow::Owned(BitVec::from_fn(n, |j| j == (id + 1) % n || id == (j + 1) % n))
+    }
+}
+
+fn connect<G: GraphMut>(mut g: G, n: usize) {
+    for i in 1..n {
+        g.add_edge(0, i);
+    }
+}
+
+#[test]
+fn test_read_only_impl() {
+    let c = Cycle(7);
+    let (dist, _) = utils::bfs(&c, 0, &mut |_,_,_| {});
+    assert!(dist == vec![0, 1, 2, 3, 3, 2, 1]);
+    assert!(utils::greedy_color(&c, &(0..7).collect::<Vec<_>>()).len() == 3);
+}
+
+#[test]
+fn test_references() {
+    let mut g = graph::new(4);
+    connect(&mut g, 4);
+    let r = &g;
+    assert!(r.has_edge(3, 0) && utils::dfs(&r, 1, &mut |_| {})[3] >= 0);
+}
+
+#[test]
+fn test_mutable_reference_overrides() {
+    // through &mut, a forbidden loop reaches the graph's own try_add_edge, not the default
+    fn attempt<G: GraphMut>(mut g: G) -> Vec<Result<(), GraphError>> {
+        vec![g.try_add_edge(1, 1), g.try_add_edges(0, &[1, 2]), g.try_contract((1, 2))]
+    }
+    let mut g = graph::with_loops(3, LoopPolicy::Forbid);
+    assert_eq!(attempt(&mut g), vec![Err(GraphError::SelfLoop(1)), Ok(()),
+                                     Err(GraphError::MissingEdge(1, 2))]);
+    assert!(g.has_edge(0, 2) && !g.has_edge(1, 1));
+}
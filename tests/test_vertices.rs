@@ -0,0 +1,41 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut, Removal};
+use bitgraphs::{graph, digraph, packed, hybrid};
+
+fn exercise<G: BitGraph>(mut g: G) {
+    // path 0 - 1 - 2 - 3 built one vertex at a time
+    for v in 0..4 {
+        assert!(g.add_vertex() == v);
+        if v > 0 {
+            g.add_edge(v - 1, v);
+        }
+    }
+    assert!(g.len() == 4 && g.has_edge(2, 3) && g.verify());
+    assert!(g.remove_vertex(1, Removal::Tombstone).is_none());
+    assert!(g.len() == 4 && !g.has_edge(0, 1) && !g.has_edge(1, 2) && g.has_edge(2, 3));
+    // 3 moves into slot 0, keeping its edge to 2
+    assert!(g.remove_vertex(0, Removal::SwapRemove) == Some(3));
+    assert!(g.len() == 3 && g.has_edge(2, 0) && !g.has_edge(0, 1) && g.verify());
+    assert!(g.remove_vertex(2, Removal::SwapRemove).is_none());
+    assert!(g.len() == 2 && !g.out_neighbors(0).any());
+}
+
+#[test]
+fn test_vertices() {
+    exercise(graph::new(0));
+    exercise(digraph::new(0));
+    exercise(packed::new(0));
+    exercise(hybrid::new(0));
+    exercise(hybrid::new_directed(0));
+}
+
+#[test]
+fn test_grow_large() {
+    let mut g = graph::new(0);
+    for v in 0..300 {
+        g.add_vertex();
+        if v > 0 {
+            g.add_edge(0, v);
+        }
+    }
+    assert!(g.verify() && g.out_degree(0) == 299);
+}
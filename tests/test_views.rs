@@ -0,0 +1,40 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut};
+use bitgraphs::{graph, digraph, utils, views};
+use bitgraphs::bitset::BitVec;
+
+#[test]
+fn test_views() {
+    // K_4 minus the edge 0-1: the complement has exactly that edge
+    let mut g = graph::complete(4);
+    g.remove_edge(0, 1);
+    let c = views::complement(&g);
+    assert!(c.has_edge(1, 0) && !c.has_edge(2, 3) && !c.has_edge(2, 2));
+    assert!(c.out_neighbors(0) == g.complement().out_neighbors(0));
+    assert!(utils::is_clique(&c, &BitVec::from_fn(4, |i| i < 2)));
+    // every class of a coloring of the complement is a clique of g
+    for class in utils::greedy_color(&c, &[0, 1, 2, 3]) {
+        assert!(utils::is_clique(&g, class.get_ref()));
+    }
+
+    let mask = BitVec::from_fn(4, |i| i != 2);
+    let sub = views::induced(&g, &mask);
+    let mut h = g.clone();
+    h.induce(&mask);
+    assert!((0..4).all(|v| sub.out_neighbors(v) == h.out_neighbors(v)));
+    assert!(sub.has_edge(0, 3) && !sub.has_edge(1, 2));
+}
+
+#[test]
+fn test_transpose_bfs() {
+    let mut g = digraph::new(4);
+    g.add_edge(0, 1);
+    g.add_edge(1, 2);
+    g.add_edge(2, 3);
+    let r = views::transpose(&g);
+    assert!(r.has_edge(3, 2) && !r.has_edge(2, 3));
+    let (dist, _) = utils::bfs(&r, 3, &mut |_,_,_| {});
+    assert!(dist == vec![3, 2, 1, 0]);
+    // views nest
+    let rr = views::transpose(&r);
+    assert!(rr.out_neighbors(1) == g.out_neighbors(1));
+}
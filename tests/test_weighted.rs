@@ -0,0 +1,52 @@
+use bitgraphs::{BitGraph, GraphRef, GraphMut, GraphError, Removal};
+use bitgraphs::{graph, digraph, weighted};
+
+#[test]
+fn test_weights() {
+    let mut g = weighted::new(graph::new(4), 0u32);
+    g.add_weighted_edge(0, 1, 5);
+    g.add_weighted_edge(2, 1, 3);
+    g.add_edge(2, 3);
+    assert!(g.weight(1, 0) == Some(&5) && g.weight(1, 2) == Some(&3) && g.weight(3, 2) == Some(&0));
+    assert!(g.set_weight(0, 3, 1) == Err(GraphError::MissingEdge(0, 3)));
+    g.set_weight(3, 2, 7).unwrap();
+    let edges: Vec<_> = g.weighted_edges().map(|(u, v, &w)| (u, v, w)).collect();
+    assert_eq!(edges, vec![(0, 1, 5), (1, 2, 3), (2, 3, 7)]);
+    assert!(g.remove_weighted_edge(2, 1) == Some(3) && !g.has_edge(1, 2) && g.verify());
+
+    let mut d = weighted::new(digraph::new(3), 1.0f64);
+    d.add_weighted_edge(0, 1, 2.5);
+    assert!(d.weight(0, 1) == Some(&2.5) && d.weight(1, 0).is_none());
+    assert_eq!(d.try_add_edge(5, 0), Err(GraphError::OutOfBounds { vertex: 5, len: 3 }));
+    assert_eq!(d.try_add_edge(0, 5), Err(GraphError::OutOfBounds { vertex: 5, len: 3 }));
+    assert!(d.try_add_edge(2, 0).is_ok() && d.weight(2, 0) == Some(&0.0) && d.verify());
+}
+
+#[test]
+fn test_contract_weights() {
+    // triangle 0-1-2 plus pendant 1-3; contracting 0-1 merges 0-2 with 1-2
+    let mut g = weighted::new(graph::new(4), 0);
+    g.add_weighted_edge(0, 1, 1);
+    g.add_weighted_edge(0, 2, 2);
+    g.add_weighted_edge(1, 2, 4);
+    g.add_weighted_edge(1, 3, 8);
+    g.contract_with((0, 1), |a, b| a + b);
+    assert!(g.weight(0, 2) == Some(&6) && g.weight(0, 3) == Some(&8));
+    assert!(g.weight(0, 0).is_none() && !g.has_edge(1, 3) && g.verify());
+}
+
+#[test]
+fn test_remap_weights() {
+    let mut g = weighted::new(graph::new(5), 0);
+    g.add_weighted_edge(1, 3, 10);
+    g.add_weighted_edge(3, 4, 20);
+    let (c, map) = g.compressed();
+    assert_eq!(map, vec![1, 3, 4]);
+    assert!(c.weight(0, 1) == Some(&10) && c.weight(2, 1) == Some(&20) && c.verify());
+
+    let r = g.reordered(&[4, 3, 2, 1, 0]);
+    assert!(r.weight(3, 1) == Some(&10) && r.weight(0, 1) == Some(&20) && r.verify());
+
+    assert!(g.remove_vertex(0, Removal::SwapRemove) == Some(4));
+    assert!(g.weight(0, 3) == Some(&20) && g.weight(1, 3) == Some(&10) && g.verify());
+}
@@ -1,9 +1,33 @@
-#![feature(collections)]
 extern crate bitgraphs;
 
+mod test_attributed;
+mod test_bipartite;
+mod test_bitset;
 mod test_csv;
 mod test_construction;
+mod test_degrees;
+mod test_dimacs;
+mod test_dot;
+mod test_edgelist;
+mod test_errors;
+mod test_graph6;
+mod test_graphml;
+mod test_hybrid;
+mod test_hypergraph;
 mod test_induce;
+mod test_journal;
+mod test_kernels;
+mod test_labeled;
+mod test_layered;
+mod test_loops;
+mod test_packed;
+mod test_persistent;
 mod test_reorder;
+mod test_std_traits;
+mod test_tournament;
+mod test_traits;
 mod test_utils;
+mod test_vertices;
+mod test_views;
+mod test_weighted;
 